@@ -0,0 +1,82 @@
+use crate::{
+    matrix::{self, Matrix3f},
+    vec::Vector2f,
+};
+
+/// An affine map split as `translate * rotate * shear * scale`; a reflection shows up as a negative y scale.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Decomposition {
+    pub translation: Vector2f,
+    pub rotation: f32,
+    pub scale: Vector2f,
+    pub shear: f32,
+    pub reflection: bool,
+}
+
+impl Decomposition {
+    /// Returns `None` when the linear part is singular.
+    pub fn from_matrix(m: &Matrix3f) -> Option<Decomposition> {
+        let (a, b, c, d) = (m.get(0, 0), m.get(0, 1), m.get(1, 0), m.get(1, 1));
+        let determinant = a * d - b * c;
+        let scale_x = a.hypot(c);
+        if scale_x < f32::EPSILON || determinant.abs() < f32::EPSILON {
+            return None;
+        }
+
+        Some(Decomposition {
+            translation: Vector2f::new(m.get(0, 2), m.get(1, 2)),
+            rotation: c.atan2(a),
+            scale: Vector2f::new(scale_x, determinant / scale_x),
+            shear: (a * b + c * d) / determinant,
+            reflection: determinant < 0.0,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FitError {
+    PointCountMismatch,
+    Degenerate,
+}
+
+impl std::fmt::Display for FitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FitError::PointCountMismatch => write!(f, "the shapes have different numbers of points"),
+            FitError::Degenerate => write!(f, "the points are collinear or too few"),
+        }
+    }
+}
+
+/// Least-squares affine map taking each `source` point to the `target` point at the same index.
+pub fn fit(source: &[Vector2f], target: &[Vector2f]) -> Result<Matrix3f, FitError> {
+    if source.len() != target.len() {
+        return Err(FitError::PointCountMismatch);
+    }
+    if source.len() < 3 {
+        return Err(FitError::Degenerate);
+    }
+
+    let mut normal = [[0.0f64; 3]; 3];
+    let mut rhs_x = [0.0f64; 3];
+    let mut rhs_y = [0.0f64; 3];
+    for (s, t) in source.iter().zip(target) {
+        let row = [s.x() as f64, s.y() as f64, 1.0];
+        for i in 0..3 {
+            for j in 0..3 {
+                normal[i][j] += row[i] * row[j];
+            }
+            rhs_x[i] += row[i] * t.x() as f64;
+            rhs_y[i] += row[i] * t.y() as f64;
+        }
+    }
+
+    let x = matrix::solve(normal, rhs_x).ok_or(FitError::Degenerate)?;
+    let y = matrix::solve(normal, rhs_y).ok_or(FitError::Degenerate)?;
+
+    Ok(Matrix3f::from_rows([
+        [x[0] as f32, x[1] as f32, x[2] as f32],
+        [y[0] as f32, y[1] as f32, y[2] as f32],
+        [0.0, 0.0, 1.0],
+    ]))
+}
@@ -0,0 +1,214 @@
+use crate::vec::Vector2f;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Aabb {
+    pub min: Vector2f,
+    pub max: Vector2f,
+}
+
+impl Aabb {
+    pub fn new(min: Vector2f, max: Vector2f) -> Self {
+        Self { min, max }
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            min: Vector2f::new(f32::INFINITY, f32::INFINITY),
+            max: Vector2f::new(f32::NEG_INFINITY, f32::NEG_INFINITY),
+        }
+    }
+
+    pub fn from_corners(a: Vector2f, b: Vector2f) -> Self {
+        Self {
+            min: Vector2f::new(a.x().min(b.x()), a.y().min(b.y())),
+            max: Vector2f::new(a.x().max(b.x()), a.y().max(b.y())),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.x() > self.max.x() || self.min.y() > self.max.y()
+    }
+
+    pub fn union(&self, other: &Aabb) -> Aabb {
+        Aabb {
+            min: Vector2f::new(
+                self.min.x().min(other.min.x()),
+                self.min.y().min(other.min.y()),
+            ),
+            max: Vector2f::new(
+                self.max.x().max(other.max.x()),
+                self.max.y().max(other.max.y()),
+            ),
+        }
+    }
+
+    pub fn expanded(&self, margin: f32) -> Aabb {
+        Aabb {
+            min: self.min - Vector2f::new(margin, margin),
+            max: self.max + Vector2f::new(margin, margin),
+        }
+    }
+
+    pub fn center(&self) -> Vector2f {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn contains_point(&self, point: Vector2f) -> bool {
+        point.x() >= self.min.x()
+            && point.x() <= self.max.x()
+            && point.y() >= self.min.y()
+            && point.y() <= self.max.y()
+    }
+
+    pub fn contains(&self, other: &Aabb) -> bool {
+        !other.is_empty() && self.contains_point(other.min) && self.contains_point(other.max)
+    }
+
+    pub fn intersects(&self, other: &Aabb) -> bool {
+        self.min.x() <= other.max.x()
+            && self.max.x() >= other.min.x()
+            && self.min.y() <= other.max.y()
+            && self.max.y() >= other.min.y()
+    }
+}
+
+#[derive(Debug, Clone)]
+enum NodeKind {
+    Leaf(usize),
+    Branch(usize, usize),
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    bounds: Aabb,
+    parent: Option<usize>,
+    kind: NodeKind,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Bvh {
+    nodes: Vec<Node>,
+    root: Option<usize>,
+    leaves: Vec<usize>,
+}
+
+impl Bvh {
+    pub fn build(items: &[Aabb]) -> Bvh {
+        let mut bvh = Bvh {
+            nodes: Vec::with_capacity(items.len() * 2),
+            root: None,
+            leaves: vec![0; items.len()],
+        };
+
+        let mut indices: Vec<usize> = (0..items.len()).collect();
+        if !indices.is_empty() {
+            let root = bvh.build_node(items, &mut indices, None);
+            bvh.root = Some(root);
+        }
+
+        bvh
+    }
+
+    fn build_node(
+        &mut self,
+        items: &[Aabb],
+        indices: &mut [usize],
+        parent: Option<usize>,
+    ) -> usize {
+        let node = self.nodes.len();
+
+        if let [item] = indices {
+            self.nodes.push(Node {
+                bounds: items[*item],
+                parent,
+                kind: NodeKind::Leaf(*item),
+            });
+            self.leaves[*item] = node;
+            return node;
+        }
+
+        let centers = indices
+            .iter()
+            .map(|i| items[*i])
+            .filter(|b| !b.is_empty())
+            .fold(Aabb::empty(), |acc, b| {
+                acc.union(&Aabb::new(b.center(), b.center()))
+            });
+        let split_on_x = centers.is_empty()
+            || centers.max.x() - centers.min.x() >= centers.max.y() - centers.min.y();
+        let key = |i: &usize| {
+            let bounds = items[*i];
+            match (bounds.is_empty(), split_on_x) {
+                (true, _) => f32::INFINITY,
+                (false, true) => bounds.center().x(),
+                (false, false) => bounds.center().y(),
+            }
+        };
+        indices.sort_by(|a, b| key(a).total_cmp(&key(b)));
+
+        self.nodes.push(Node {
+            bounds: Aabb::empty(),
+            parent,
+            kind: NodeKind::Leaf(0),
+        });
+
+        let (left, right) = indices.split_at_mut(indices.len() / 2);
+        let left = self.build_node(items, left, Some(node));
+        let right = self.build_node(items, right, Some(node));
+
+        self.nodes[node].bounds = self.nodes[left].bounds.union(&self.nodes[right].bounds);
+        self.nodes[node].kind = NodeKind::Branch(left, right);
+
+        node
+    }
+
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    pub fn update(&mut self, item: usize, bounds: Aabb) {
+        let Some(&leaf) = self.leaves.get(item) else {
+            return;
+        };
+
+        self.nodes[leaf].bounds = bounds;
+        let mut current = self.nodes[leaf].parent;
+        while let Some(node) = current {
+            if let NodeKind::Branch(left, right) = self.nodes[node].kind {
+                self.nodes[node].bounds = self.nodes[left].bounds.union(&self.nodes[right].bounds);
+            }
+            current = self.nodes[node].parent;
+        }
+    }
+
+    pub fn query_point(&self, point: Vector2f, margin: f32) -> Vec<usize> {
+        self.query(|bounds| bounds.expanded(margin).contains_point(point))
+    }
+
+    pub fn query_rect(&self, rect: &Aabb) -> Vec<usize> {
+        self.query(|bounds| bounds.intersects(rect))
+    }
+
+    fn query(&self, test: impl Fn(&Aabb) -> bool) -> Vec<usize> {
+        let mut result = Vec::new();
+        let mut stack: Vec<usize> = self.root.into_iter().collect();
+
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+            if node.bounds.is_empty() || !test(&node.bounds) {
+                continue;
+            }
+
+            match node.kind {
+                NodeKind::Leaf(item) => result.push(item),
+                NodeKind::Branch(left, right) => {
+                    stack.push(left);
+                    stack.push(right);
+                }
+            }
+        }
+
+        result.sort_unstable();
+        result
+    }
+}
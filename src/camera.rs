@@ -0,0 +1,105 @@
+use crate::{
+    matrix::Matrix4f,
+    vec::{Vector3f, Vector4f},
+};
+
+const MAX_PITCH: f32 = 1.55;
+const NEAR: f32 = 1.0;
+const FAR: f32 = 10000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Projection {
+    Orthographic,
+    Perspective,
+}
+
+impl Projection {
+    pub const ALL: [Projection; 2] = [Projection::Orthographic, Projection::Perspective];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Projection::Orthographic => "Orthographic",
+            Projection::Perspective => "Perspective",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Projection> {
+        Projection::ALL.into_iter().find(|p| p.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrbitCamera {
+    pub yaw: f32,
+    pub pitch: f32,
+    pub distance: f32,
+    pub fov: f32,
+    pub projection: Projection,
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        Self {
+            yaw: 0.5,
+            pitch: 0.4,
+            distance: 900.0,
+            fov: 60.0,
+            projection: Projection::Perspective,
+        }
+    }
+}
+
+impl OrbitCamera {
+    pub fn orbit(&mut self, yaw: f32, pitch: f32) {
+        self.yaw += yaw;
+        self.pitch = (self.pitch + pitch).clamp(-MAX_PITCH, MAX_PITCH);
+    }
+
+    pub fn eye(&self) -> Vector3f {
+        Vector3f::new(
+            self.pitch.cos() * self.yaw.sin(),
+            self.pitch.sin(),
+            self.pitch.cos() * self.yaw.cos(),
+        ) * self.distance
+    }
+
+    pub fn view_matrix(&self) -> Matrix4f {
+        Matrix4f::look_at(self.eye(), Vector3f::zero(), Vector3f::new(0.0, 1.0, 0.0))
+    }
+
+    /// The orthographic view keeps the same visible size as the perspective one at the orbit target.
+    pub fn projection_matrix(&self, aspect: f32) -> Matrix4f {
+        let half_height = self.distance * (self.fov.to_radians() / 2.0).tan();
+
+        match self.projection {
+            Projection::Orthographic => {
+                Matrix4f::orthographic(half_height * aspect, half_height, NEAR, FAR)
+            }
+            Projection::Perspective => {
+                Matrix4f::perspective(self.fov.to_radians(), aspect, NEAR, FAR)
+            }
+        }
+    }
+}
+
+/// Clips a clip-space polygon to the near plane (`z >= -w`), so faces crossing behind the camera are cut
+/// rather than dropped. Returns an empty polygon when the face is entirely behind it.
+pub fn clip_near(polygon: &[Vector4f]) -> Vec<Vector4f> {
+    let distance = |p: &Vector4f| p.z() + p.w();
+    let mut clipped = Vec::with_capacity(polygon.len() + 1);
+
+    for (i, current) in polygon.iter().enumerate() {
+        let next = &polygon[(i + 1) % polygon.len()];
+        let (d_current, d_next) = (distance(current), distance(next));
+
+        if d_current >= 0.0 {
+            clipped.push(*current);
+        }
+        if (d_current >= 0.0) != (d_next >= 0.0) {
+            let t = d_current / (d_current - d_next);
+            clipped.push(*current + (*next - *current) * t);
+        }
+    }
+
+    clipped
+}
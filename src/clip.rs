@@ -0,0 +1,314 @@
+use crate::{
+    decompose,
+    shape::{ring_contains, signed_area, Shape, FLATTEN_TOLERANCE},
+    vec::Vector2f,
+};
+
+pub fn rectangle(min: Vector2f, max: Vector2f) -> Vec<Vector2f> {
+    vec![
+        min,
+        Vector2f::new(max.x(), min.y()),
+        max,
+        Vector2f::new(min.x(), max.y()),
+    ]
+}
+
+/// Clips `shape` against a clip polygon; Sutherland–Hodgman when both are convex, Weiler–Atherton otherwise.
+pub fn clip_shape(shape: &Shape, clip: &[Vector2f]) -> Vec<Shape> {
+    let outline = shape.flatten(FLATTEN_TOLERANCE);
+
+    let mut pieces: Vec<Shape> = if shape.is_closed() {
+        clip_polygon(&outline, clip)
+            .into_iter()
+            .map(|ring| {
+                let mut piece = Shape::from_points(ring);
+                piece.set_fill_rule(shape.fill_rule());
+                piece
+            })
+            .collect()
+    } else {
+        clip_polyline(&outline, clip)
+            .into_iter()
+            .map(|line| {
+                let mut piece = Shape::from_points(line);
+                piece.set_closed(false);
+                piece
+            })
+            .collect()
+    };
+
+    let holes = if shape.is_closed() {
+        shape.holes().as_slice()
+    } else {
+        &[]
+    };
+    for ring in holes.iter().flat_map(|hole| clip_polygon(hole, clip)) {
+        if let Some(piece) = pieces.iter_mut().find(|p| p.intersect_with_point(ring[0])) {
+            piece.add_hole(ring);
+        }
+    }
+
+    for piece in pieces.iter_mut() {
+        piece.set_group(shape.group());
+    }
+
+    pieces
+}
+
+pub fn clip_polygon(subject: &[Vector2f], clip: &[Vector2f]) -> Vec<Vec<Vector2f>> {
+    if subject.len() < 3 || clip.len() < 3 {
+        return Vec::new();
+    }
+
+    // Sutherland–Hodgman keeps one ring, so a concave subject cut into several pieces would come back
+    // joined by zero-width bridges along the clip edges.
+    if decompose::is_convex(clip) && decompose::is_convex(subject) {
+        let ring = sutherland_hodgman(subject, clip);
+        return if ring.len() >= 3 {
+            vec![ring]
+        } else {
+            Vec::new()
+        };
+    }
+
+    weiler_atherton(subject, clip)
+}
+
+pub fn sutherland_hodgman(subject: &[Vector2f], clip: &[Vector2f]) -> Vec<Vector2f> {
+    let orientation = signed_area(clip).signum();
+    let inside =
+        |point: Vector2f, a: Vector2f, b: Vector2f| (b - a).cross(point - a) * orientation >= 0.0;
+
+    let mut output = subject.to_vec();
+    for i in 0..clip.len() {
+        let (a, b) = (clip[i], clip[(i + 1) % clip.len()]);
+        let input = std::mem::take(&mut output);
+        let Some(mut prev) = input.last().copied() else {
+            break;
+        };
+
+        for point in input {
+            match (inside(point, a, b), inside(prev, a, b)) {
+                (true, true) => output.push(point),
+                (true, false) => {
+                    output.extend(line_intersection(prev, point, a, b));
+                    output.push(point);
+                }
+                (false, true) => output.extend(line_intersection(prev, point, a, b)),
+                (false, false) => {}
+            }
+            prev = point;
+        }
+    }
+
+    output
+}
+
+/// Keeps the parts of an open polyline that lie inside `clip`.
+pub fn clip_polyline(line: &[Vector2f], clip: &[Vector2f]) -> Vec<Vec<Vector2f>> {
+    let mut pieces: Vec<Vec<Vector2f>> = Vec::new();
+    let mut current: Vec<Vector2f> = Vec::new();
+
+    for edge in line.windows(2) {
+        let (start, end) = (edge[0], edge[1]);
+        let mut ts: Vec<f32> = vec![0.0, 1.0];
+        for i in 0..clip.len() {
+            if let Some((t, _)) = segment_params(start, end, clip[i], clip[(i + 1) % clip.len()]) {
+                ts.push(t);
+            }
+        }
+        ts.sort_by(f32::total_cmp);
+
+        for pair in ts.windows(2) {
+            let (from, to) = (
+                start + (end - start) * pair[0],
+                start + (end - start) * pair[1],
+            );
+            if ring_contains(clip, (from + to) / 2.0) {
+                if current
+                    .last()
+                    .is_none_or(|last| last.distance_to(from) > f32::EPSILON)
+                {
+                    if current.len() > 1 {
+                        pieces.push(std::mem::take(&mut current));
+                    }
+                    current = vec![from];
+                }
+                current.push(to);
+            } else if current.len() > 1 {
+                pieces.push(std::mem::take(&mut current));
+            } else {
+                current.clear();
+            }
+        }
+    }
+
+    if current.len() > 1 {
+        pieces.push(current);
+    }
+
+    pieces
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Intersection {
+    subject_edge: (usize, f32),
+    clip_edge: (usize, f32),
+    point: Vector2f,
+    entering: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    point: Vector2f,
+    crossing: Option<usize>,
+}
+
+fn weiler_atherton(subject: &[Vector2f], clip: &[Vector2f]) -> Vec<Vec<Vector2f>> {
+    let subject = counter_clockwise(subject);
+    let clip = counter_clockwise(clip);
+
+    let mut crossings = Vec::new();
+    for i in 0..subject.len() {
+        let (a, b) = (subject[i], subject[(i + 1) % subject.len()]);
+        for j in 0..clip.len() {
+            let (c, d) = (clip[j], clip[(j + 1) % clip.len()]);
+            if let Some((t, u)) = segment_params(a, b, c, d) {
+                crossings.push(Intersection {
+                    subject_edge: (i, t),
+                    clip_edge: (j, u),
+                    point: a + (b - a) * t,
+                    entering: (d - c).cross(b - a) > 0.0,
+                });
+            }
+        }
+    }
+
+    if crossings.is_empty() {
+        return if ring_contains(&clip, subject[0]) {
+            vec![subject]
+        } else if ring_contains(&subject, clip[0]) {
+            vec![clip]
+        } else {
+            Vec::new()
+        };
+    }
+
+    let subject_nodes = with_crossings(&subject, &crossings, |c| c.subject_edge);
+    let clip_nodes = with_crossings(&clip, &crossings, |c| c.clip_edge);
+    let position = |nodes: &[Node], crossing: usize| {
+        nodes
+            .iter()
+            .position(|n| n.crossing == Some(crossing))
+            .unwrap()
+    };
+
+    let mut visited = vec![false; crossings.len()];
+    let mut result = Vec::new();
+    for start in 0..crossings.len() {
+        if visited[start] || !crossings[start].entering {
+            continue;
+        }
+
+        let mut ring = Vec::new();
+        let mut on_subject = true;
+        let mut index = position(&subject_nodes, start);
+        for _ in 0..subject_nodes.len() + clip_nodes.len() {
+            let nodes = if on_subject {
+                &subject_nodes
+            } else {
+                &clip_nodes
+            };
+            let node = nodes[index];
+            if let Some(crossing) = node.crossing {
+                if crossing == start && !ring.is_empty() {
+                    break;
+                }
+                visited[crossing] = true;
+                if !ring.is_empty() {
+                    on_subject = !on_subject;
+                    let nodes = if on_subject {
+                        &subject_nodes
+                    } else {
+                        &clip_nodes
+                    };
+                    index = (position(nodes, crossing) + 1) % nodes.len();
+                    ring.push(node.point);
+                    continue;
+                }
+            }
+
+            ring.push(node.point);
+            index = (index + 1) % nodes.len();
+        }
+
+        if ring.len() >= 3 {
+            result.push(ring);
+        }
+    }
+
+    result
+}
+
+fn with_crossings(
+    ring: &[Vector2f],
+    crossings: &[Intersection],
+    key: impl Fn(&Intersection) -> (usize, f32),
+) -> Vec<Node> {
+    let mut nodes = Vec::new();
+    for (i, point) in ring.iter().enumerate() {
+        nodes.push(Node {
+            point: *point,
+            crossing: None,
+        });
+
+        let mut on_edge: Vec<(f32, usize)> = crossings
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| key(c).0 == i)
+            .map(|(k, c)| (key(c).1, k))
+            .collect();
+        on_edge.sort_by(|a, b| a.0.total_cmp(&b.0));
+        nodes.extend(on_edge.into_iter().map(|(_, k)| Node {
+            point: crossings[k].point,
+            crossing: Some(k),
+        }));
+    }
+
+    nodes
+}
+
+fn counter_clockwise(ring: &[Vector2f]) -> Vec<Vector2f> {
+    let mut ring = ring.to_vec();
+    if signed_area(&ring) < 0.0 {
+        ring.reverse();
+    }
+    ring
+}
+
+fn segment_params(a: Vector2f, b: Vector2f, c: Vector2f, d: Vector2f) -> Option<(f32, f32)> {
+    let ab = b - a;
+    let cd = d - c;
+    let denominator = ab.cross(cd);
+    if denominator.abs() <= f32::EPSILON {
+        return None;
+    }
+
+    let t = (c - a).cross(cd) / denominator;
+    let u = (c - a).cross(ab) / denominator;
+    if (0.0..1.0).contains(&t) && (0.0..1.0).contains(&u) {
+        Some((t, u))
+    } else {
+        None
+    }
+}
+
+fn line_intersection(p: Vector2f, q: Vector2f, a: Vector2f, b: Vector2f) -> Option<Vector2f> {
+    let pq = q - p;
+    let denominator = pq.cross(b - a);
+    if denominator.abs() <= f32::EPSILON {
+        return None;
+    }
+
+    Some(p + pq * ((a - p).cross(b - a) / denominator))
+}
@@ -0,0 +1,111 @@
+use js_sys::{Array, Function, Object, Promise, Reflect};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{window, Blob, BlobPropertyBag, DataTransfer, Event};
+
+const PASTE_FLAVORS: [&str; 3] = ["text/plain", "image/svg+xml", "text/html"];
+const SHAPES_PROMPT: &str = "Copy the shapes:";
+
+/// Writes in the background; if the browser refuses, the text is offered in a prompt instead.
+pub fn write_text(text: &str, prompt: &str) {
+    let text = text.to_string();
+    let prompt = prompt.to_string();
+
+    spawn_local(async move {
+        if !try_write_text(&text).await {
+            prompt_copy(&prompt, &text);
+        }
+    });
+}
+
+pub fn write_shapes(json: &str, svg: &str) {
+    let json = json.to_string();
+    let item = clipboard_item(&[("text/plain", json.as_str()), ("image/svg+xml", svg)]);
+
+    spawn_local(async move {
+        if let (Some(clipboard), Some(item)) = (navigator_clipboard(), item) {
+            if call_method(&clipboard, "write", &Array::of1(&item).into()).await {
+                return;
+            }
+        }
+        if !try_write_text(&json).await {
+            prompt_copy(SHAPES_PROMPT, &json);
+        }
+    });
+}
+
+pub fn paste_event_texts(event: &Event) -> Vec<String> {
+    let Ok(data) = Reflect::get(event, &"clipboardData".into()) else {
+        return Vec::new();
+    };
+    let Ok(data) = data.dyn_into::<DataTransfer>() else {
+        return Vec::new();
+    };
+
+    PASTE_FLAVORS
+        .iter()
+        .filter_map(|flavor| data.get_data(flavor).ok())
+        .filter(|text| !text.is_empty())
+        .collect()
+}
+
+async fn try_write_text(text: &str) -> bool {
+    let Some(clipboard) = navigator_clipboard() else {
+        return false;
+    };
+
+    call_method(&clipboard, "writeText", &text.into()).await
+}
+
+fn prompt_copy(message: &str, text: &str) {
+    if let Some(window) = window() {
+        let _ = window.prompt_with_message_and_default(message, text);
+    }
+}
+
+fn navigator_clipboard() -> Option<JsValue> {
+    let navigator = window()?.navigator();
+    let clipboard = Reflect::get(&navigator, &"clipboard".into()).ok()?;
+
+    if clipboard.is_undefined() {
+        None
+    } else {
+        Some(clipboard)
+    }
+}
+
+fn clipboard_item(flavors: &[(&str, &str)]) -> Option<JsValue> {
+    let constructor = Reflect::get(&js_sys::global(), &"ClipboardItem".into()).ok()?;
+    let constructor = constructor.dyn_into::<Function>().ok()?;
+
+    let items = Object::new();
+    for (flavor, text) in flavors {
+        let blob = Blob::new_with_str_sequence_and_options(
+            &Array::of1(&(*text).into()),
+            BlobPropertyBag::new().type_(flavor),
+        )
+        .ok()?;
+        Reflect::set(&items, &(*flavor).into(), &blob).ok()?;
+    }
+
+    Reflect::construct(&constructor, &Array::of1(&items)).ok()
+}
+
+/// Clipboard methods return promises, so success is only known once they settle.
+async fn call_method(target: &JsValue, name: &str, arg: &JsValue) -> bool {
+    let Ok(method) = Reflect::get(target, &name.into()) else {
+        return false;
+    };
+    let Ok(method) = method.dyn_into::<Function>() else {
+        return false;
+    };
+
+    let Ok(result) = method.call1(target, arg) else {
+        return false;
+    };
+
+    match result.dyn_into::<Promise>() {
+        Ok(promise) => JsFuture::from(promise).await.is_ok(),
+        Err(_) => true,
+    }
+}
@@ -0,0 +1,219 @@
+use crate::{
+    decompose,
+    shape::{Shape, FLATTEN_TOLERANCE},
+    vec::Vector2f,
+};
+
+const MAX_GJK_ITERATIONS: usize = 64;
+const MAX_RESOLVE_ITERATIONS: usize = 8;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Contact {
+    pub mtv: Vector2f,
+    pub depth: f32,
+}
+
+/// Translation that moves `a` out of `b`, or `None` when they do not overlap.
+///
+/// Concave shapes are compared piece by piece. The deepest piece-pair MTV alone may leave other pieces
+/// overlapping, so the MTVs are accumulated until no pair overlaps or `MAX_RESOLVE_ITERATIONS` is reached;
+/// the result is then the separating translation found so far, not necessarily the minimal one.
+/// Holes are ignored: a shape resting inside another's hole still counts as overlapping.
+pub fn collide(a: &Shape, b: &Shape) -> Option<Contact> {
+    let (a_min, a_max) = a.bounds()?;
+    let (b_min, b_max) = b.bounds()?;
+    if a_max.x() < b_min.x()
+        || b_max.x() < a_min.x()
+        || a_max.y() < b_min.y()
+        || b_max.y() < a_min.y()
+    {
+        return None;
+    }
+
+    let a_pieces = convex_pieces(a);
+    let b_pieces = convex_pieces(b);
+
+    let mut total: Option<Vector2f> = None;
+    for _ in 0..MAX_RESOLVE_ITERATIONS {
+        let offset = total.unwrap_or_else(Vector2f::zero);
+        let Some(deepest) = deepest_contact(&a_pieces, &b_pieces, offset) else {
+            break;
+        };
+        total = Some(offset + deepest.mtv);
+    }
+
+    total.map(|mtv| Contact {
+        mtv,
+        depth: mtv.length(),
+    })
+}
+
+fn deepest_contact(a_pieces: &[Vec<Vector2f>], b_pieces: &[Vec<Vector2f>], offset: Vector2f) -> Option<Contact> {
+    let mut deepest: Option<Contact> = None;
+    for a_piece in a_pieces {
+        let a_piece: Vec<Vector2f> = a_piece.iter().map(|p| *p + offset).collect();
+        for b_piece in b_pieces {
+            if !gjk_intersects(&a_piece, b_piece) {
+                continue;
+            }
+
+            let Some(contact) = sat_mtv(&a_piece, b_piece) else {
+                continue;
+            };
+            if contact.depth > f32::EPSILON && deepest.is_none_or(|d| contact.depth > d.depth) {
+                deepest = Some(contact);
+            }
+        }
+    }
+
+    deepest
+}
+
+/// Splits a shape into convex polygons; open shapes become their individual edges.
+pub fn convex_pieces(shape: &Shape) -> Vec<Vec<Vector2f>> {
+    let outline = shape.flatten(FLATTEN_TOLERANCE);
+
+    if !shape.is_closed() {
+        return outline.windows(2).map(|edge| edge.to_vec()).collect();
+    }
+
+    decompose::convex_decomposition(&outline)
+}
+
+pub fn gjk_intersects(a: &[Vector2f], b: &[Vector2f]) -> bool {
+    if a.is_empty() || b.is_empty() {
+        return false;
+    }
+
+    let mut direction = Vector2f::new(1.0, 0.0);
+    let mut simplex = vec![minkowski_support(a, b, direction)];
+    direction = -simplex[0];
+
+    for _ in 0..MAX_GJK_ITERATIONS {
+        if direction.dot(direction) == 0.0 {
+            return true;
+        }
+
+        let point = minkowski_support(a, b, direction);
+        if point.dot(direction) <= 0.0 {
+            return false;
+        }
+
+        simplex.push(point);
+        if update_simplex(&mut simplex, &mut direction) {
+            return true;
+        }
+    }
+
+    false
+}
+
+pub fn sat_mtv(a: &[Vector2f], b: &[Vector2f]) -> Option<Contact> {
+    let mut best: Option<Contact> = None;
+
+    for axis in edge_normals(a).into_iter().chain(edge_normals(b)) {
+        let (a_min, a_max) = project(a, axis);
+        let (b_min, b_max) = project(b, axis);
+        if a_max <= b_min || b_max <= a_min {
+            return None;
+        }
+
+        let contact = if a_max - b_min < b_max - a_min {
+            Contact {
+                mtv: axis * -(a_max - b_min),
+                depth: a_max - b_min,
+            }
+        } else {
+            Contact {
+                mtv: axis * (b_max - a_min),
+                depth: b_max - a_min,
+            }
+        };
+        if best.is_none_or(|b| contact.depth < b.depth) {
+            best = Some(contact);
+        }
+    }
+
+    best
+}
+
+fn update_simplex(simplex: &mut Vec<Vector2f>, direction: &mut Vector2f) -> bool {
+    let a = *simplex.last().unwrap();
+    let to_origin = -a;
+
+    match simplex[..] {
+        [b, _] => {
+            let ab = b - a;
+            if ab.dot(to_origin) > 0.0 {
+                let normal = perpendicular_towards(ab, to_origin);
+                if normal.dot(to_origin) == 0.0 {
+                    return true;
+                }
+                *direction = normal;
+            } else {
+                *simplex = vec![a];
+                *direction = to_origin;
+            }
+
+            false
+        }
+        [c, b, _] => {
+            let ab = b - a;
+            let ac = c - a;
+            let ab_normal = perpendicular_towards(ab, a - c);
+            let ac_normal = perpendicular_towards(ac, a - b);
+
+            if ab_normal.dot(to_origin) > 0.0 {
+                *simplex = vec![b, a];
+                *direction = ab_normal;
+                false
+            } else if ac_normal.dot(to_origin) > 0.0 {
+                *simplex = vec![c, a];
+                *direction = ac_normal;
+                false
+            } else {
+                true
+            }
+        }
+        _ => false,
+    }
+}
+
+fn perpendicular_towards(edge: Vector2f, towards: Vector2f) -> Vector2f {
+    let normal = Vector2f::new(-edge.y(), edge.x());
+    if normal.dot(towards) < 0.0 {
+        -normal
+    } else {
+        normal
+    }
+}
+
+fn minkowski_support(a: &[Vector2f], b: &[Vector2f], direction: Vector2f) -> Vector2f {
+    support(a, direction) - support(b, -direction)
+}
+
+fn support(points: &[Vector2f], direction: Vector2f) -> Vector2f {
+    *points
+        .iter()
+        .max_by(|p, q| p.dot(direction).total_cmp(&q.dot(direction)))
+        .unwrap()
+}
+
+fn edge_normals(points: &[Vector2f]) -> Vec<Vector2f> {
+    let edges = if points.len() == 2 { 1 } else { points.len() };
+
+    (0..edges)
+        .map(|i| points[(i + 1) % points.len()] - points[i])
+        .filter(|edge| edge.length() > 0.0)
+        .map(|edge| Vector2f::new(-edge.y(), edge.x()).normalize())
+        .collect()
+}
+
+fn project(points: &[Vector2f], axis: Vector2f) -> (f32, f32) {
+    points
+        .iter()
+        .map(|p| p.dot(axis))
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), d| {
+            (min.min(d), max.max(d))
+        })
+}
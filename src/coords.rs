@@ -0,0 +1,125 @@
+use crate::{matrix::Matrix3f, vec::Vector2f};
+
+const MIN_TICK_SPACING: f32 = 40.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Origin {
+    TopLeft,
+    Center,
+    BottomLeft,
+}
+
+impl Origin {
+    pub const ALL: [Origin; 3] = [Origin::TopLeft, Origin::Center, Origin::BottomLeft];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Origin::TopLeft => "Top left",
+            Origin::Center => "Center",
+            Origin::BottomLeft => "Bottom left",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Origin> {
+        Origin::ALL.into_iter().find(|o| o.name() == name)
+    }
+}
+
+/// How canvas pixels map to the numbers shown to the user; shapes themselves always stay in pixels.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoordinateSystem {
+    pub origin: Origin,
+    pub y_up: bool,
+    pub pixels_per_unit: f32,
+    pub unit: String,
+    pub show_axes: bool,
+}
+
+impl Default for CoordinateSystem {
+    fn default() -> Self {
+        Self {
+            origin: Origin::TopLeft,
+            y_up: false,
+            pixels_per_unit: 1.0,
+            unit: "px".to_string(),
+            show_axes: true,
+        }
+    }
+}
+
+impl CoordinateSystem {
+    pub fn origin_position(&self, canvas: Vector2f) -> Vector2f {
+        match self.origin {
+            Origin::TopLeft => Vector2f::zero(),
+            Origin::Center => canvas / 2.0,
+            Origin::BottomLeft => Vector2f::new(0.0, canvas.y()),
+        }
+    }
+
+    fn axis_signs(&self) -> Vector2f {
+        Vector2f::new(1.0, if self.y_up { -1.0 } else { 1.0 })
+    }
+
+    pub fn vector_to_world(&self, vector: Vector2f) -> Vector2f {
+        let signs = self.axis_signs();
+        Vector2f::new(vector.x() * signs.x(), vector.y() * signs.y()) / self.pixels_per_unit
+    }
+
+    pub fn vector_to_screen(&self, vector: Vector2f) -> Vector2f {
+        let signs = self.axis_signs();
+        Vector2f::new(vector.x() * signs.x(), vector.y() * signs.y()) * self.pixels_per_unit
+    }
+
+    pub fn to_world(&self, point: Vector2f, canvas: Vector2f) -> Vector2f {
+        self.vector_to_world(point - self.origin_position(canvas))
+    }
+
+    pub fn to_screen(&self, point: Vector2f, canvas: Vector2f) -> Vector2f {
+        self.origin_position(canvas) + self.vector_to_screen(point)
+    }
+
+    pub fn length_to_world(&self, length: f32) -> f32 {
+        length / self.pixels_per_unit
+    }
+
+    /// Square pixels to square units.
+    pub fn area_to_world(&self, area: f32) -> f32 {
+        area / (self.pixels_per_unit * self.pixels_per_unit)
+    }
+
+    /// Flipping y mirrors the plane, so a counter-clockwise world angle is clockwise on screen.
+    pub fn angle_to_world(&self, degrees: f32) -> f32 {
+        if self.y_up {
+            -degrees
+        } else {
+            degrees
+        }
+    }
+
+    pub fn angle_to_screen(&self, degrees: f32) -> f32 {
+        self.angle_to_world(degrees)
+    }
+
+    /// Re-expresses a transform of screen points as the same transform acting on world points.
+    pub fn matrix_to_world(&self, matrix: &Matrix3f, canvas: Vector2f) -> Matrix3f {
+        let signs = self.axis_signs();
+        let to_screen = Matrix3f::translation(self.origin_position(canvas))
+            * Matrix3f::scaling(signs * self.pixels_per_unit);
+        let to_world = Matrix3f::scaling(signs / self.pixels_per_unit)
+            * Matrix3f::translation(-self.origin_position(canvas));
+
+        to_world * *matrix * to_screen
+    }
+
+    /// World distance between axis ticks: the smallest 1, 2 or 5 times a power of ten that keeps ticks readable.
+    pub fn tick_spacing(&self) -> f32 {
+        let minimum = MIN_TICK_SPACING / self.pixels_per_unit;
+        let magnitude = 10f32.powf(minimum.log10().floor());
+
+        [1.0, 2.0, 5.0, 10.0]
+            .into_iter()
+            .map(|factor| factor * magnitude)
+            .find(|spacing| *spacing >= minimum)
+            .unwrap_or(magnitude * 10.0)
+    }
+}
@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+use crate::vec::Vector2f;
+
+const MAX_FLATTEN_DEPTH: u32 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Segment {
+    Line,
+    Quadratic(Vector2f),
+    Cubic(Vector2f, Vector2f),
+    CatmullRom,
+}
+
+impl Segment {
+    pub fn from_kind(kind: CurveKind, start: Vector2f, end: Vector2f) -> Segment {
+        match kind {
+            CurveKind::Line => Segment::Line,
+            CurveKind::Quadratic => Segment::Quadratic((start + end) / 2.0),
+            CurveKind::Cubic => Segment::Cubic(
+                start + (end - start) / 3.0,
+                start + (end - start) * (2.0 / 3.0),
+            ),
+            CurveKind::CatmullRom => Segment::CatmullRom,
+        }
+    }
+
+    pub fn control_points(&self) -> Vec<Vector2f> {
+        match *self {
+            Segment::Quadratic(control) => vec![control],
+            Segment::Cubic(first, second) => vec![first, second],
+            Segment::Line | Segment::CatmullRom => Vec::new(),
+        }
+    }
+
+    pub fn control_points_mut(&mut self) -> Vec<&mut Vector2f> {
+        match self {
+            Segment::Quadratic(control) => vec![control],
+            Segment::Cubic(first, second) => vec![first, second],
+            Segment::Line | Segment::CatmullRom => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CurveKind {
+    Line,
+    Quadratic,
+    Cubic,
+    CatmullRom,
+}
+
+impl CurveKind {
+    pub const ALL: [CurveKind; 4] = [
+        CurveKind::Line,
+        CurveKind::Quadratic,
+        CurveKind::Cubic,
+        CurveKind::CatmullRom,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CurveKind::Line => "Line",
+            CurveKind::Quadratic => "Quadratic Bezier",
+            CurveKind::Cubic => "Cubic Bezier",
+            CurveKind::CatmullRom => "Catmull-Rom",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<CurveKind> {
+        CurveKind::ALL.into_iter().find(|k| k.name() == name)
+    }
+}
+
+pub fn quadratic_to_cubic(
+    start: Vector2f,
+    control: Vector2f,
+    end: Vector2f,
+) -> (Vector2f, Vector2f) {
+    (
+        start + (control - start) * (2.0 / 3.0),
+        end + (control - end) * (2.0 / 3.0),
+    )
+}
+
+pub fn catmull_rom_to_cubic(
+    previous: Vector2f,
+    start: Vector2f,
+    end: Vector2f,
+    next: Vector2f,
+) -> (Vector2f, Vector2f) {
+    (start + (end - previous) / 6.0, end - (next - start) / 6.0)
+}
+
+/// Appends the points of a cubic Bezier to `out`, excluding `start` and including `end`.
+pub fn flatten_cubic(
+    start: Vector2f,
+    first: Vector2f,
+    second: Vector2f,
+    end: Vector2f,
+    tolerance: f32,
+    out: &mut Vec<Vector2f>,
+) {
+    flatten_cubic_rec(start, first, second, end, tolerance, 0, out);
+    out.push(end);
+}
+
+fn flatten_cubic_rec(
+    start: Vector2f,
+    first: Vector2f,
+    second: Vector2f,
+    end: Vector2f,
+    tolerance: f32,
+    depth: u32,
+    out: &mut Vec<Vector2f>,
+) {
+    if depth >= MAX_FLATTEN_DEPTH
+        || (distance_to_chord(first, start, end) <= tolerance
+            && distance_to_chord(second, start, end) <= tolerance)
+    {
+        return;
+    }
+
+    let start_first = (start + first) / 2.0;
+    let first_second = (first + second) / 2.0;
+    let second_end = (second + end) / 2.0;
+    let left = (start_first + first_second) / 2.0;
+    let right = (first_second + second_end) / 2.0;
+    let middle = (left + right) / 2.0;
+
+    flatten_cubic_rec(start, start_first, left, middle, tolerance, depth + 1, out);
+    out.push(middle);
+    flatten_cubic_rec(middle, right, second_end, end, tolerance, depth + 1, out);
+}
+
+/// Distance to the chord as a segment rather than a line, so a control point overshooting either end
+/// keeps a collinear curve from counting as flat.
+fn distance_to_chord(point: Vector2f, start: Vector2f, end: Vector2f) -> f32 {
+    let chord = end - start;
+    let length_squared = chord.dot(chord);
+    if length_squared == 0.0 {
+        return point.distance_to(start);
+    }
+
+    let t = ((point - start).dot(chord) / length_squared).clamp(0.0, 1.0);
+    point.distance_to(start + chord * t)
+}
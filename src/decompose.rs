@@ -0,0 +1,127 @@
+use crate::{shape::signed_area, vec::Vector2f};
+
+/// Hertel–Mehlhorn: triangulate, then drop every diagonal whose removal keeps both sides convex.
+pub fn convex_decomposition(ring: &[Vector2f]) -> Vec<Vec<Vector2f>> {
+    if ring.len() < 3 || is_convex(ring) {
+        return vec![ring.to_vec()];
+    }
+
+    let mut pieces: Vec<Vec<usize>> = triangulate(ring)
+        .into_iter()
+        .map(|triangle| triangle.to_vec())
+        .collect();
+
+    let mut merged = true;
+    while merged {
+        merged = false;
+
+        'search: for a in 0..pieces.len() {
+            for b in a + 1..pieces.len() {
+                let Some(candidate) = merge_pieces(&pieces[a], &pieces[b]) else {
+                    continue;
+                };
+
+                let points: Vec<Vector2f> = candidate.iter().map(|&i| ring[i]).collect();
+                if is_convex(&points) {
+                    pieces[a] = candidate;
+                    pieces.remove(b);
+                    merged = true;
+                    break 'search;
+                }
+            }
+        }
+    }
+
+    pieces
+        .into_iter()
+        .map(|piece| piece.into_iter().map(|i| ring[i]).collect())
+        .collect()
+}
+
+pub fn is_convex(ring: &[Vector2f]) -> bool {
+    let n = ring.len();
+    if n < 3 {
+        return true;
+    }
+
+    let mut sign = 0.0f32;
+    for i in 0..n {
+        let a = ring[i];
+        let b = ring[(i + 1) % n];
+        let c = ring[(i + 2) % n];
+        let cross = (b - a).cross(c - b);
+        if cross.abs() <= f32::EPSILON {
+            continue;
+        }
+        if sign != 0.0 && cross.signum() != sign {
+            return false;
+        }
+        sign = cross.signum();
+    }
+
+    true
+}
+
+/// Ear-clipping triangulation of a simple polygon, as indices into `ring`.
+pub fn triangulate(ring: &[Vector2f]) -> Vec<[usize; 3]> {
+    let orientation = signed_area(ring).signum();
+    let mut remaining: Vec<usize> = (0..ring.len()).collect();
+    let mut triangles = Vec::new();
+
+    while remaining.len() > 3 {
+        let n = remaining.len();
+        let corner = |i: usize| {
+            [
+                remaining[(i + n - 1) % n],
+                remaining[i],
+                remaining[(i + 1) % n],
+            ]
+        };
+
+        let ear = (0..n)
+            .find(|&i| {
+                let [a, b, c] = corner(i).map(|j| ring[j]);
+                (b - a).cross(c - b) * orientation > 0.0
+                    && remaining
+                        .iter()
+                        .map(|&j| ring[j])
+                        .all(|p| !point_in_triangle(p, a, b, c))
+            })
+            .unwrap_or(0);
+
+        triangles.push(corner(ear));
+        remaining.remove(ear);
+    }
+
+    if let [a, b, c] = remaining[..] {
+        triangles.push([a, b, c]);
+    }
+
+    triangles
+}
+
+fn merge_pieces(a: &[usize], b: &[usize]) -> Option<Vec<usize>> {
+    let (start, end) = (0..a.len())
+        .map(|i| (a[i], a[(i + 1) % a.len()]))
+        .find(|&(start, end)| (0..b.len()).any(|j| b[j] == end && b[(j + 1) % b.len()] == start))?;
+
+    let a_from = a.iter().position(|&i| i == end)?;
+    let b_from = b.iter().position(|&i| i == start)?;
+
+    let mut merged: Vec<usize> = (0..a.len()).map(|k| a[(a_from + k) % a.len()]).collect();
+    merged.extend((1..b.len() - 1).map(|k| b[(b_from + k) % b.len()]));
+
+    Some(merged)
+}
+
+fn point_in_triangle(point: Vector2f, a: Vector2f, b: Vector2f, c: Vector2f) -> bool {
+    if [a, b, c].iter().any(|v| v.distance_to(point) == 0.0) {
+        return false;
+    }
+
+    let d1 = (b - a).cross(point - a);
+    let d2 = (c - b).cross(point - b);
+    let d3 = (a - c).cross(point - c);
+
+    (d1 >= 0.0 && d2 >= 0.0 && d3 >= 0.0) || (d1 <= 0.0 && d2 <= 0.0 && d3 <= 0.0)
+}
@@ -0,0 +1,218 @@
+use crate::vec::Vector2f;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LatticeInterpolation {
+    Bilinear,
+    Bezier,
+}
+
+impl LatticeInterpolation {
+    pub const ALL: [LatticeInterpolation; 2] =
+        [LatticeInterpolation::Bilinear, LatticeInterpolation::Bezier];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LatticeInterpolation::Bilinear => "Bilinear",
+            LatticeInterpolation::Bezier => "Bezier",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<LatticeInterpolation> {
+        LatticeInterpolation::ALL
+            .into_iter()
+            .find(|i| i.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WarpKind {
+    Twist,
+    Bend,
+    Bulge,
+    Wave,
+}
+
+impl WarpKind {
+    pub const ALL: [WarpKind; 4] = [WarpKind::Twist, WarpKind::Bend, WarpKind::Bulge, WarpKind::Wave];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            WarpKind::Twist => "Twist",
+            WarpKind::Bend => "Bend",
+            WarpKind::Bulge => "Bulge",
+            WarpKind::Wave => "Wave",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<WarpKind> {
+        WarpKind::ALL.into_iter().find(|k| k.name() == name)
+    }
+
+    pub fn amount_label(&self) -> &'static str {
+        match self {
+            WarpKind::Twist | WarpKind::Bend => "Angle (°): ",
+            WarpKind::Bulge => "Amount: ",
+            WarpKind::Wave => "Amplitude (px): ",
+        }
+    }
+
+    /// Warps `point` relative to the box `min`..`max`; twist and bend take `amount` in degrees,
+    /// bulge a relative scale and wave an amplitude in pixels.
+    pub fn apply(&self, amount: f32, min: Vector2f, max: Vector2f, point: Vector2f) -> Vector2f {
+        let center = (min + max) / 2.0;
+        let size = max - min;
+        let radius = size.length() / 2.0;
+        let offset = point - center;
+
+        match self {
+            WarpKind::Twist => {
+                if radius == 0.0 {
+                    return point;
+                }
+                let falloff = (1.0 - offset.length() / radius).max(0.0);
+                center + offset.rotate(amount.to_radians() * falloff)
+            }
+            WarpKind::Bend => {
+                let angle = amount.to_radians();
+                if angle.abs() < f32::EPSILON || size.x() == 0.0 {
+                    return point;
+                }
+                let bend_radius = size.x() / angle;
+                let phi = offset.x() / bend_radius;
+                let distance = bend_radius - offset.y();
+                Vector2f::new(
+                    center.x() + distance * phi.sin(),
+                    center.y() + bend_radius - distance * phi.cos(),
+                )
+            }
+            WarpKind::Bulge => {
+                if radius == 0.0 {
+                    return point;
+                }
+                let t = (offset.length() / radius).min(1.0);
+                center + offset * (1.0 + amount * (1.0 - t * t))
+            }
+            WarpKind::Wave => {
+                if size.x() == 0.0 {
+                    return point;
+                }
+                let phase = offset.x() / size.x() * 4.0 * std::f32::consts::PI;
+                point + Vector2f::new(0.0, amount * phase.sin())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DeformSettings {
+    pub divisions: u32,
+    pub interpolation: LatticeInterpolation,
+    pub subdivision: f32,
+    pub warp: WarpKind,
+    pub amount: f32,
+}
+
+impl Default for DeformSettings {
+    fn default() -> Self {
+        Self {
+            divisions: 3,
+            interpolation: LatticeInterpolation::Bilinear,
+            subdivision: 5.0,
+            warp: WarpKind::Twist,
+            amount: 45.0,
+        }
+    }
+}
+
+/// A grid of control points over a box; moving them deforms everything inside the box.
+#[derive(Debug, Clone)]
+pub struct Lattice {
+    min: Vector2f,
+    max: Vector2f,
+    divisions: usize,
+    interpolation: LatticeInterpolation,
+    points: Vec<Vector2f>,
+}
+
+impl Lattice {
+    pub fn new(
+        min: Vector2f,
+        max: Vector2f,
+        divisions: u32,
+        interpolation: LatticeInterpolation,
+    ) -> Lattice {
+        let divisions = divisions.max(1) as usize;
+        let size = max - min;
+        let points = (0..=divisions)
+            .flat_map(|row| {
+                (0..=divisions).map(move |column| {
+                    min + Vector2f::new(
+                        size.x() * column as f32 / divisions as f32,
+                        size.y() * row as f32 / divisions as f32,
+                    )
+                })
+            })
+            .collect();
+
+        Lattice {
+            min,
+            max,
+            divisions,
+            interpolation,
+            points,
+        }
+    }
+
+    pub fn divisions(&self) -> usize {
+        self.divisions
+    }
+
+    pub fn points(&self) -> &[Vector2f] {
+        &self.points
+    }
+
+    pub fn move_point(&mut self, index: usize, position: Vector2f) {
+        if let Some(point) = self.points.get_mut(index) {
+            *point = position;
+        }
+    }
+
+    pub fn apply(&self, point: Vector2f) -> Vector2f {
+        let size = self.max - self.min;
+        if size.x() == 0.0 || size.y() == 0.0 {
+            return point;
+        }
+
+        let u = ((point.x() - self.min.x()) / size.x()).clamp(0.0, 1.0);
+        let v = ((point.y() - self.min.y()) / size.y()).clamp(0.0, 1.0);
+        let n = self.divisions;
+        let at = |row: usize, column: usize| self.points[row * (n + 1) + column];
+
+        match self.interpolation {
+            LatticeInterpolation::Bilinear => {
+                let (x, y) = (u * n as f32, v * n as f32);
+                let column = (x.floor() as usize).min(n - 1);
+                let row = (y.floor() as usize).min(n - 1);
+                let (s, t) = (x - column as f32, y - row as f32);
+
+                let top = at(row, column) * (1.0 - s) + at(row, column + 1) * s;
+                let bottom = at(row + 1, column) * (1.0 - s) + at(row + 1, column + 1) * s;
+                top * (1.0 - t) + bottom * t
+            }
+            LatticeInterpolation::Bezier => {
+                let mut result = Vector2f::zero();
+                for row in 0..=n {
+                    for column in 0..=n {
+                        result += at(row, column) * (bernstein(n, column, u) * bernstein(n, row, v));
+                    }
+                }
+                result
+            }
+        }
+    }
+}
+
+fn bernstein(n: usize, i: usize, t: f32) -> f32 {
+    let binomial = (0..i).fold(1.0, |acc, k| acc * (n - k) as f32 / (k + 1) as f32);
+    binomial * t.powi(i as i32) * (1.0 - t).powi((n - i) as i32)
+}
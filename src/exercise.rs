@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{explain::Stage, metrics, shape::Shape, vec::Vector2f};
+
+fn default_tolerance() -> f32 {
+    5.0
+}
+
+/// A start shape the student transforms until it matches the target within `tolerance` pixels.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Exercise {
+    pub title: String,
+    #[serde(default)]
+    pub description: String,
+    pub start: Shape,
+    pub target: Shape,
+    #[serde(default = "default_tolerance")]
+    pub tolerance: f32,
+}
+
+impl Exercise {
+    /// Hausdorff distance to the target; the exercise is solved once it is within tolerance.
+    pub fn score(&self, shape: &Shape) -> Score {
+        let distance = metrics::shape_hausdorff(shape, &self.target);
+
+        Score {
+            distance,
+            passed: distance <= self.tolerance,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Score {
+    pub distance: f32,
+    pub passed: bool,
+}
+
+/// One press of "Apply": the stages run in `order`, with scale and rotate about the pivot.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TransformStep {
+    pub shift: Vector2f,
+    pub scale: Vector2f,
+    pub rotation: f32,
+    pub pivot: Vector2f,
+    pub order: [Stage; 3],
+}
+
+impl TransformStep {
+    pub fn describe(&self) -> String {
+        let parts: Vec<String> = self
+            .order
+            .iter()
+            .filter_map(|stage| match stage {
+                Stage::Shift if self.shift != Vector2f::zero() => {
+                    Some(format!("shift ({}, {})", self.shift.x(), self.shift.y()))
+                }
+                Stage::Scale if self.scale != Vector2f::new(1.0, 1.0) => {
+                    Some(format!("scale ({}, {})", self.scale.x(), self.scale.y()))
+                }
+                Stage::Rotate if self.rotation != 0.0 => Some(format!("rotate {}°", self.rotation)),
+                _ => None,
+            })
+            .collect();
+        if parts.is_empty() {
+            return "no change".to_string();
+        }
+
+        format!(
+            "{} about ({}, {})",
+            parts.join(", "),
+            self.pivot.x(),
+            self.pivot.y()
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Report<'a> {
+    pub exercise: &'a str,
+    pub score: Score,
+    pub steps: &'a [TransformStep],
+}
+
+/// Accepts either a single exercise or an array of them.
+pub fn parse(json: &str) -> Result<Vec<Exercise>, serde_json::Error> {
+    serde_json::from_str::<Vec<Exercise>>(json)
+        .or_else(|_| serde_json::from_str::<Exercise>(json).map(|e| vec![e]))
+}
+
+/// The exercises bundled with the app, kept in `exercises.json` in the same format users load.
+pub fn builtin() -> Vec<Exercise> {
+    parse(include_str!("exercises.json")).expect("bundled exercises are valid")
+}
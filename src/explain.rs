@@ -0,0 +1,61 @@
+use serde::Serialize;
+
+use crate::{exercise::TransformStep, matrix::Matrix3f, vec::Vector2f};
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub enum Stage {
+    Shift,
+    Scale,
+    Rotate,
+}
+
+impl Stage {
+    pub const DEFAULT_ORDER: [Stage; 3] = [Stage::Shift, Stage::Scale, Stage::Rotate];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Stage::Shift => "Shift",
+            Stage::Scale => "Scale",
+            Stage::Rotate => "Rotate",
+        }
+    }
+
+    pub fn color(&self) -> &'static str {
+        match self {
+            Stage::Shift => "steelblue",
+            Stage::Scale => "seagreen",
+            Stage::Rotate => "crimson",
+        }
+    }
+}
+
+/// The matrix for `stage` carried `t` of the way from the identity; scale and rotate act about the pivot.
+pub fn stage_matrix(step: &TransformStep, stage: Stage, t: f32) -> Matrix3f {
+    let about_pivot = |m: Matrix3f| {
+        Matrix3f::translation(step.pivot) * m * Matrix3f::translation(-step.pivot)
+    };
+
+    match stage {
+        Stage::Shift => Matrix3f::translation(step.shift * t),
+        Stage::Scale => {
+            let one = Vector2f::new(1.0, 1.0);
+            about_pivot(Matrix3f::scaling(one + (step.scale - one) * t))
+        }
+        Stage::Rotate => about_pivot(Matrix3f::rotation(step.rotation.to_radians() * t)),
+    }
+}
+
+/// The stages composed in order, with `progress` counting finished stages and its fraction animating the next.
+pub fn at_progress(step: &TransformStep, progress: f32) -> Matrix3f {
+    step.order
+        .iter()
+        .enumerate()
+        .fold(Matrix3f::identity(), |acc, (i, stage)| {
+            let t = (progress - i as f32).clamp(0.0, 1.0);
+            stage_matrix(step, *stage, t) * acc
+        })
+}
+
+pub fn composite(step: &TransformStep) -> Matrix3f {
+    at_progress(step, step.order.len() as f32)
+}
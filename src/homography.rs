@@ -0,0 +1,90 @@
+use crate::{
+    matrix::{self, Matrix3f},
+    vec::{Vector2f, Vector3f},
+};
+
+const MIN_W: f32 = 1e-6;
+/// Sine of the shallowest corner a quad may have before three of its points count as collinear.
+const MIN_CORNER_SINE: f32 = 1e-4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Homography {
+    matrix: Matrix3f,
+}
+
+impl Homography {
+    /// Maps each `source[i]` onto `target[i]`; `None` when either quad is degenerate or folded, i.e.
+    /// has repeated or collinear corners, is not convex, or sends part of the source to infinity.
+    pub fn from_points(source: [Vector2f; 4], target: [Vector2f; 4]) -> Option<Homography> {
+        if !is_valid_quad(&source) || !is_valid_quad(&target) {
+            return None;
+        }
+
+        let mut a = [[0.0f64; 8]; 8];
+        let mut b = [0.0f64; 8];
+        for i in 0..4 {
+            let (x, y) = (source[i].x() as f64, source[i].y() as f64);
+            let (u, v) = (target[i].x() as f64, target[i].y() as f64);
+
+            a[2 * i] = [x, y, 1.0, 0.0, 0.0, 0.0, -u * x, -u * y];
+            b[2 * i] = u;
+            a[2 * i + 1] = [0.0, 0.0, 0.0, x, y, 1.0, -v * x, -v * y];
+            b[2 * i + 1] = v;
+        }
+
+        let h = matrix::solve(a, b)?.map(|value| value as f32);
+        let mut homography = Homography {
+            matrix: Matrix3f::from_rows([
+                [h[0], h[1], h[2]],
+                [h[3], h[4], h[5]],
+                [h[6], h[7], 1.0],
+            ]),
+        };
+
+        // w is affine in the source point, so positive w at the corners means positive w across the quad.
+        if homography.w(source[0]) < 0.0 {
+            homography.matrix = Matrix3f::from_rows(homography.matrix.rows().map(|row| row.map(|v| -v)));
+        }
+        if source.iter().any(|p| homography.w(*p) <= MIN_W) {
+            return None;
+        }
+
+        Some(homography)
+    }
+
+    fn w(&self, point: Vector2f) -> f32 {
+        (self.matrix * Vector3f::new(point.x(), point.y(), 1.0)).z()
+    }
+
+    pub fn matrix(&self) -> &Matrix3f {
+        &self.matrix
+    }
+
+    pub fn apply(&self, point: Vector2f) -> Vector2f {
+        self.matrix.transform_point(point)
+    }
+
+    /// `None` for points on or behind the line the homography sends to infinity.
+    pub fn apply_checked(&self, point: Vector2f) -> Option<Vector2f> {
+        (self.w(point) > MIN_W).then(|| self.apply(point))
+    }
+}
+
+/// Convex with every corner turning the same way by a clear margin, which also rules out repeated
+/// corners, three collinear corners and self-intersecting quads.
+fn is_valid_quad(quad: &[Vector2f; 4]) -> bool {
+    let turns: Vec<f32> = (0..4)
+        .map(|i| {
+            let (a, b, c) = (quad[i], quad[(i + 1) % 4], quad[(i + 2) % 4]);
+            let (incoming, outgoing) = (b - a, c - b);
+            let sine = incoming.cross(outgoing) / (incoming.length() * outgoing.length());
+            if sine.is_finite() {
+                sine
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    turns.iter().all(|sine| *sine > MIN_CORNER_SINE) || turns.iter().all(|sine| *sine < -MIN_CORNER_SINE)
+}
@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+use web_sys::{window, KeyboardEvent};
+
+const STORAGE_KEY: &str = "transformations.keymap";
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Action {
+    ModeDraw,
+    ModeRotate,
+    ModeScale,
+    ModeShift,
+    ModePrimitive,
+    ModeEdit,
+    ModeHole,
+    ModeSelect,
+    ModeThreeD,
+    ModeWarp,
+    ModeLattice,
+    ModeExercise,
+    ModeExplain,
+    Nudge(f32, f32),
+    Delete,
+    Cancel,
+    FinishShape,
+    Copy,
+    Cut,
+    Duplicate,
+    ToggleClosed,
+}
+
+impl Action {
+    pub fn label(&self) -> String {
+        match self {
+            Action::ModeDraw => "Draw mode".to_string(),
+            Action::ModeRotate => "Rotate mode".to_string(),
+            Action::ModeScale => "Scale mode".to_string(),
+            Action::ModeShift => "Shift mode".to_string(),
+            Action::ModePrimitive => "Primitive mode".to_string(),
+            Action::ModeEdit => "Edit mode".to_string(),
+            Action::ModeHole => "Hole mode".to_string(),
+            Action::ModeSelect => "Select mode".to_string(),
+            Action::ModeThreeD => "3D mode".to_string(),
+            Action::ModeWarp => "Perspective warp mode".to_string(),
+            Action::ModeLattice => "Lattice deform mode".to_string(),
+            Action::ModeExercise => "Exercise mode".to_string(),
+            Action::ModeExplain => "Explain transform mode".to_string(),
+            Action::Nudge(x, y) => format!("Nudge by ({}, {})", x, y),
+            Action::Delete => "Delete shape".to_string(),
+            Action::Cancel => "Cancel drawing".to_string(),
+            Action::FinishShape => "Finish shape".to_string(),
+            Action::Copy => "Copy".to_string(),
+            Action::Cut => "Cut".to_string(),
+            Action::Duplicate => "Duplicate".to_string(),
+            Action::ToggleClosed => "Open/close shape".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeyBinding {
+    pub key: String,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyBinding {
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            ctrl: false,
+            shift: false,
+            alt: false,
+        }
+    }
+
+    pub fn ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    pub fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub fn from_event(event: &KeyboardEvent) -> Self {
+        let key = event.key();
+        let key = if key.chars().count() == 1 {
+            key.to_lowercase()
+        } else {
+            key
+        };
+
+        Self {
+            key,
+            ctrl: event.ctrl_key() || event.meta_key(),
+            shift: event.shift_key(),
+            alt: event.alt_key(),
+        }
+    }
+
+    pub fn is_modifier(&self) -> bool {
+        matches!(self.key.as_str(), "Control" | "Shift" | "Alt" | "Meta")
+    }
+}
+
+impl std::fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if self.shift {
+            write!(f, "Shift+")?;
+        }
+
+        match self.key.as_str() {
+            " " => write!(f, "Space"),
+            key if key.chars().count() == 1 => write!(f, "{}", key.to_uppercase()),
+            key => write!(f, "{}", key),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keymap {
+    bindings: Vec<(KeyBinding, Action)>,
+    #[serde(skip)]
+    capturing: Option<usize>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut bindings = vec![
+            (KeyBinding::new("d"), Action::ModeDraw),
+            (KeyBinding::new("r"), Action::ModeRotate),
+            (KeyBinding::new("s"), Action::ModeScale),
+            (KeyBinding::new("t"), Action::ModeShift),
+            (KeyBinding::new("p"), Action::ModePrimitive),
+            (KeyBinding::new("e"), Action::ModeEdit),
+            (KeyBinding::new("h"), Action::ModeHole),
+            (KeyBinding::new("v"), Action::ModeSelect),
+            (KeyBinding::new("3"), Action::ModeThreeD),
+            (KeyBinding::new("w"), Action::ModeWarp),
+            (KeyBinding::new("l"), Action::ModeLattice),
+            (KeyBinding::new("x"), Action::ModeExercise),
+            (KeyBinding::new("m"), Action::ModeExplain),
+            (KeyBinding::new("o"), Action::ToggleClosed),
+        ];
+
+        let arrows = [
+            ("ArrowLeft", -1.0, 0.0),
+            ("ArrowRight", 1.0, 0.0),
+            ("ArrowUp", 0.0, -1.0),
+            ("ArrowDown", 0.0, 1.0),
+        ];
+        for (key, x, y) in arrows {
+            bindings.push((KeyBinding::new(key), Action::Nudge(x, y)));
+            bindings.push((
+                KeyBinding::new(key).shift(),
+                Action::Nudge(x * 10.0, y * 10.0),
+            ));
+        }
+
+        bindings.extend([
+            (KeyBinding::new("Delete"), Action::Delete),
+            (KeyBinding::new("Backspace"), Action::Delete),
+            (KeyBinding::new("Escape"), Action::Cancel),
+            (KeyBinding::new("Enter"), Action::FinishShape),
+            (KeyBinding::new("c").ctrl(), Action::Copy),
+            (KeyBinding::new("x").ctrl(), Action::Cut),
+            (KeyBinding::new("d").ctrl(), Action::Duplicate),
+        ]);
+
+        Self {
+            bindings,
+            capturing: None,
+        }
+    }
+}
+
+impl Keymap {
+    pub fn load() -> Self {
+        let saved: Option<Keymap> = window()
+            .and_then(|w| w.local_storage().ok().flatten())
+            .and_then(|storage| storage.get_item(STORAGE_KEY).ok().flatten())
+            .and_then(|json| serde_json::from_str(&json).ok());
+
+        match saved {
+            Some(mut keymap) => {
+                keymap.merge_defaults();
+                keymap
+            }
+            None => Self::default(),
+        }
+    }
+
+    /// Saved keymaps predate actions added since; those get their default key unless it is already taken.
+    fn merge_defaults(&mut self) {
+        for (binding, action) in Self::default().bindings {
+            let known = self.bindings.iter().any(|(_, a)| *a == action);
+            let taken = self.bindings.iter().any(|(b, _)| *b == binding);
+            if !known && !taken {
+                self.bindings.push((binding, action));
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let Some(storage) = window().and_then(|w| w.local_storage().ok().flatten()) else {
+            return;
+        };
+
+        let _ = storage.set_item(STORAGE_KEY, &serde_json::to_string(self).unwrap());
+    }
+
+    pub fn bindings(&self) -> &[(KeyBinding, Action)] {
+        &self.bindings
+    }
+
+    pub fn action_for(&self, binding: &KeyBinding) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(b, _)| b == binding)
+            .map(|(_, action)| *action)
+    }
+
+    pub fn capturing(&self) -> Option<usize> {
+        self.capturing
+    }
+
+    pub fn start_capture(&mut self, index: usize) {
+        self.capturing = Some(index);
+    }
+
+    pub fn cancel_capture(&mut self) {
+        self.capturing = None;
+    }
+
+    pub fn capture(&mut self, binding: KeyBinding) {
+        let Some(index) = self.capturing.take() else {
+            return;
+        };
+
+        if index >= self.bindings.len() {
+            return;
+        }
+
+        // A key already bound to another action takes over this entry's old key, so no action loses its binding.
+        let previous = std::mem::replace(&mut self.bindings[index].0, binding.clone());
+        for (i, (b, _)) in self.bindings.iter_mut().enumerate() {
+            if i != index && *b == binding {
+                *b = previous.clone();
+            }
+        }
+        self.save();
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+        self.save();
+    }
+}
@@ -1,25 +1,77 @@
+mod affine;
+mod bvh;
+mod camera;
+mod clip;
+mod clipboard;
+mod collision;
+mod coords;
+mod curve;
+mod decompose;
+mod deform;
+mod exercise;
+mod explain;
+mod homography;
+mod keymap;
+mod matrix;
+mod mesh;
+mod metrics;
+mod minkowski;
+mod primitive;
+mod registration;
 mod shape;
+mod share;
+mod simplify;
+mod svg;
 mod vec;
 
-use std::{cell::RefCell, rc::Rc};
-
-use shape::Shape;
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use primitive::{Primitive, PrimitiveSettings};
+use affine::{Decomposition, FitError};
+use bvh::{Aabb, Bvh};
+use camera::{OrbitCamera, Projection};
+use collision::Contact;
+use coords::{CoordinateSystem, Origin};
+use curve::CurveKind;
+use deform::{DeformSettings, Lattice, LatticeInterpolation, WarpKind};
+use exercise::{Exercise, Score, TransformStep};
+use explain::Stage;
+use homography::Homography;
+use matrix::{Matrix3f, Matrix4f};
+use metrics::Comparison;
+use mesh::{ExtrusionSettings, Mesh};
+use registration::{FitKind, Registration, RegistrationSettings};
+use shape::{FillRule, Handle, Shape, FLATTEN_TOLERANCE};
+use simplify::Crossing;
 use wasm_bindgen::{prelude::Closure, JsCast};
-use web_sys::{window, FileReader, HtmlElement, HtmlInputElement, HtmlSelectElement};
+use web_sys::{
+    window, CanvasRenderingContext2d, CanvasWindingRule, Element, FileReader, HtmlElement, HtmlInputElement, HtmlSelectElement,
+    HtmlTextAreaElement,
+};
 use yew::{prelude::*};
 
+#[derive(PartialEq)]
 enum Mode {
     Draw,
     Rotate,
     Scale,
     Shift,
+    Primitive,
+    Edit,
+    Hole,
+    Select,
+    ThreeD,
+    Warp,
+    Lattice,
+    Exercise,
+    Explain,
 }
 
 enum Msg {
-    MouseDown(MouseEvent),
-    MouseUp(MouseEvent),
-    MouseMove(MouseEvent),
-    MouseLeave(MouseEvent),
+    PointerDown(PointerEvent),
+    PointerUp(PointerEvent),
+    PointerMove(PointerEvent),
+    PointerLeave,
     ShiftDown,
     ShiftUp,
     ModeChange(Mode),
@@ -35,8 +87,95 @@ enum Msg {
     PivotChange(vec::Vector2f),
     CtrlDown,
     CtrlUp,
+    CopyShareLink,
+    Copy,
+    Cut,
+    Paste(Vec<String>),
+    Duplicate,
+    Nudge(vec::Vector2f),
+    DeleteShape,
+    CancelShape,
+    RebindKey(usize),
+    KeyCaptured(keymap::KeyBinding),
+    ResetKeymap,
+    PrimitiveChange(Primitive),
+    PrimitiveSettingsChange(PrimitiveSettings),
+    CurveKindChange(CurveKind),
+    FillRuleChange(FillRule),
+    ToggleClosed,
+    ToggleConvexPreview,
+    DecomposeShape,
+    RepairShape,
+    ToggleClipMask,
+    ApplyClipMasks,
+    ToggleClipExport,
+    MinkowskiSum,
+    MinkowskiDifference,
+    CameraChange(OrbitCamera),
+    ExtrusionSettingsChange(ExtrusionSettings),
+    ApplyRotation3d,
+    ResetRotation3d,
+    DeformSettingsChange(DeformSettings),
+    ApplyParametricWarp,
+    SetReference,
+    MatrixInputChange(Matrix3f),
+    RegistrationSettingsChange(RegistrationSettings),
+    FitRegistration,
+    ApplyRegistration,
+    SetCompareTarget,
+    ClearCompareTarget,
+    LoadExercises(String),
+    SelectExercise(usize),
+    RestartExercise,
+    CopyExerciseReport,
+    MoveStageUp(usize),
+    ExplainPlay,
+    ExplainTick,
+    ExplainProgress(f32),
+    CoordinateSystemChange(CoordinateSystem),
+}
+
+impl Msg {
+    /// Edits that bypass the transform panel; exercise mode only allows Apply so every change is logged.
+    fn edits_shapes(&self) -> bool {
+        matches!(
+            self,
+            Msg::Clear
+                | Msg::Load(_)
+                | Msg::FinishShape
+                | Msg::Cut
+                | Msg::Paste(_)
+                | Msg::Duplicate
+                | Msg::Nudge(_)
+                | Msg::DeleteShape
+                | Msg::CurveKindChange(_)
+                | Msg::FillRuleChange(_)
+                | Msg::ToggleClosed
+                | Msg::DecomposeShape
+                | Msg::RepairShape
+                | Msg::ToggleClipMask
+                | Msg::ApplyClipMasks
+                | Msg::MinkowskiSum
+                | Msg::MinkowskiDifference
+                | Msg::ApplyRotation3d
+                | Msg::ApplyParametricWarp
+                | Msg::ApplyRegistration
+        )
+    }
 }
 
+type CollisionPair = (Rc<RefCell<Shape>>, Rc<RefCell<Shape>>, Contact);
+type CrossingCache = HashMap<*const RefCell<Shape>, (Rc<RefCell<Shape>>, Vec<Crossing>)>;
+
+const CANVAS_WIDTH: f32 = 800.0;
+const CANVAS_HEIGHT: f32 = 600.0;
+const PASTE_OFFSET: f32 = 10.0;
+const HANDLE_RADIUS: f32 = 8.0;
+const PICK_TOLERANCE: f32 = 6.0;
+const ICP_SPACING: f32 = 5.0;
+const EXPLAIN_TICK_MS: i32 = 30;
+const EXPLAIN_STEP: f32 = 0.02;
+
 struct App {
     mode: Mode,
     shapes: Vec<Rc<RefCell<Shape>>>,
@@ -47,13 +186,73 @@ struct App {
     mouse_origin: Option<vec::Vector2f>,
     mouse_pos: Option<vec::Vector2f>,
     mouse_delta: Option<vec::Vector2f>,
-    selected_shape: Option<Rc<RefCell<Shape>>>,
+    active_pointers: Vec<(i32, vec::Vector2f)>,
+    draw_pointer: Option<i32>,
+    selection: Vec<Rc<RefCell<Shape>>>,
     shift_is_down: bool,
     ctrl_is_down: bool,
 
     shift_vector: vec::Vector2f,
     scale_vector: vec::Vector2f,
     rotate_angle: f32,
+
+    share_link_length: Option<usize>,
+
+    hover_pos: Option<vec::Vector2f>,
+    paste_count: u32,
+
+    keymap: Rc<RefCell<keymap::Keymap>>,
+    is_drawing: bool,
+
+    primitive: Primitive,
+    primitive_settings: PrimitiveSettings,
+    primitive_origin: Option<vec::Vector2f>,
+
+    dragged_handle: Option<Handle>,
+    is_drawing_hole: bool,
+
+    hovered_shape: Option<Rc<RefCell<Shape>>>,
+
+    index: Bvh,
+    index_of: HashMap<*const RefCell<Shape>, usize>,
+    marquee_origin: Option<vec::Vector2f>,
+    collisions: Vec<CollisionPair>,
+    show_convex_preview: bool,
+    clip_export: bool,
+
+    camera: OrbitCamera,
+    extrusion_settings: ExtrusionSettings,
+    model_transforms: Vec<(Rc<RefCell<Shape>>, Matrix4f)>,
+
+    warp_source: [vec::Vector2f; 4],
+    warp_corners: Option<[vec::Vector2f; 4]>,
+    warp_originals: Vec<(Rc<RefCell<Shape>>, Shape)>,
+    dragged_control: Option<usize>,
+
+    deform_settings: DeformSettings,
+    lattice: Option<Lattice>,
+
+    references: Vec<(Rc<RefCell<Shape>>, Shape)>,
+    matrix_input: Matrix3f,
+
+    registration_settings: RegistrationSettings,
+    registration: Option<([Shape; 2], Registration)>,
+    compare_target: Option<Rc<RefCell<Shape>>>,
+    comparison: Option<((Shape, Shape), Comparison)>,
+    crossings: RefCell<CrossingCache>,
+
+    exercises: Vec<Exercise>,
+    exercise_index: usize,
+    exercise_shape: Option<Rc<RefCell<Shape>>>,
+    exercise_log: Vec<TransformStep>,
+    exercise_score: Option<(Shape, Score)>,
+    exercise_checkpoint: Option<Shape>,
+
+    stage_order: [Stage; 3],
+    explain_progress: f32,
+    explain_timer: Option<(i32, Closure<dyn FnMut()>)>,
+
+    coords: CoordinateSystem,
 }
 
 impl Component for App {
@@ -65,6 +264,32 @@ impl Component for App {
         let on_shift_up = ctx.link().callback(|_| Msg::ShiftUp);
         let on_ctrl_down = ctx.link().callback(|_| Msg::CtrlDown);
         let on_ctrl_up = ctx.link().callback(|_| Msg::CtrlUp);
+        let on_key_action = ctx.link().callback(|action: keymap::Action| match action {
+            keymap::Action::ModeDraw => Msg::ModeChange(Mode::Draw),
+            keymap::Action::ModeRotate => Msg::ModeChange(Mode::Rotate),
+            keymap::Action::ModeScale => Msg::ModeChange(Mode::Scale),
+            keymap::Action::ModeShift => Msg::ModeChange(Mode::Shift),
+            keymap::Action::ModePrimitive => Msg::ModeChange(Mode::Primitive),
+            keymap::Action::ModeEdit => Msg::ModeChange(Mode::Edit),
+            keymap::Action::ModeHole => Msg::ModeChange(Mode::Hole),
+            keymap::Action::ModeSelect => Msg::ModeChange(Mode::Select),
+            keymap::Action::ModeThreeD => Msg::ModeChange(Mode::ThreeD),
+            keymap::Action::ModeWarp => Msg::ModeChange(Mode::Warp),
+            keymap::Action::ModeLattice => Msg::ModeChange(Mode::Lattice),
+            keymap::Action::ModeExercise => Msg::ModeChange(Mode::Exercise),
+            keymap::Action::ModeExplain => Msg::ModeChange(Mode::Explain),
+            keymap::Action::ToggleClosed => Msg::ToggleClosed,
+            keymap::Action::Nudge(x, y) => Msg::Nudge(vec::Vector2f::new(x, y)),
+            keymap::Action::Delete => Msg::DeleteShape,
+            keymap::Action::Cancel => Msg::CancelShape,
+            keymap::Action::FinishShape => Msg::FinishShape,
+            keymap::Action::Copy => Msg::Copy,
+            keymap::Action::Cut => Msg::Cut,
+            keymap::Action::Duplicate => Msg::Duplicate,
+        });
+        let on_key_captured = ctx.link().callback(Msg::KeyCaptured);
+        let keymap = Rc::new(RefCell::new(keymap::Keymap::load()));
+        let closure_keymap = keymap.clone();
         let on_shift_down_closure = Closure::wrap(Box::new(move |event: KeyboardEvent| {
             if event.shift_key() {
                 on_shift_down.emit(());
@@ -72,6 +297,24 @@ impl Component for App {
             if event.ctrl_key() {
                 on_ctrl_down.emit(());
             }
+
+            let binding = keymap::KeyBinding::from_event(&event);
+            if binding.is_modifier() || is_text_input(&event) {
+                return;
+            }
+
+            let capturing = closure_keymap.borrow().capturing().is_some();
+            if capturing {
+                event.prevent_default();
+                on_key_captured.emit(binding);
+                return;
+            }
+
+            let action = closure_keymap.borrow().action_for(&binding);
+            if let Some(action) = action {
+                event.prevent_default();
+                on_key_action.emit(action);
+            }
         }) as Box<dyn FnMut(_)>);
         let on_shift_up_closure = Closure::wrap(Box::new(move |event: KeyboardEvent| {
             if !event.shift_key() {
@@ -98,9 +341,44 @@ impl Component for App {
         on_shift_down_closure.forget();
         on_shift_up_closure.forget();
 
-        Self {
+        let on_paste = ctx.link().callback(Msg::Paste);
+        let on_paste_closure = Closure::wrap(Box::new(move |event: Event| {
+            if is_text_input(&event) {
+                return;
+            }
+            let texts = clipboard::paste_event_texts(&event);
+            if !texts.is_empty() {
+                event.prevent_default();
+                on_paste.emit(texts);
+            }
+        }) as Box<dyn FnMut(_)>);
+
+        window()
+            .unwrap()
+            .add_event_listener_with_callback("paste", on_paste_closure.as_ref().unchecked_ref())
+            .unwrap();
+
+        on_paste_closure.forget();
+
+        let hash = window().unwrap().location().hash().unwrap_or_default();
+        let shapes = if hash.starts_with(share::FRAGMENT_PREFIX) {
+            share::decode_fragment(&hash).unwrap_or_else(|| {
+                window()
+                    .unwrap()
+                    .alert_with_message("Invalid share link")
+                    .unwrap();
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+
+        let mut app = Self {
             mode: Mode::Draw,
-            shapes: Vec::new(),
+            shapes: shapes
+                .into_iter()
+                .map(|s| Rc::new(RefCell::new(s)))
+                .collect(),
             canvas: NodeRef::default(),
             canvas_ctx: None,
             pivot: None,
@@ -108,14 +386,77 @@ impl Component for App {
             mouse_origin: None,
             mouse_pos: None,
             mouse_delta: None,
-            selected_shape: None,
+            active_pointers: Vec::new(),
+            draw_pointer: None,
+            selection: Vec::new(),
             shift_is_down: false,
             ctrl_is_down: false,
 
             shift_vector: vec::Vector2f::new(0.0, 0.0),
             scale_vector: vec::Vector2f::new(1.0, 1.0),
             rotate_angle: 0.0,
-        }
+
+            share_link_length: None,
+
+            hover_pos: None,
+            paste_count: 0,
+
+            keymap,
+            is_drawing: false,
+
+            primitive: Primitive::Rectangle,
+            primitive_settings: PrimitiveSettings::default(),
+            primitive_origin: None,
+
+            dragged_handle: None,
+            is_drawing_hole: false,
+
+            hovered_shape: None,
+
+            index: Bvh::default(),
+            index_of: HashMap::new(),
+            marquee_origin: None,
+            collisions: Vec::new(),
+            show_convex_preview: false,
+            clip_export: false,
+
+            camera: OrbitCamera::default(),
+            extrusion_settings: ExtrusionSettings::default(),
+            model_transforms: Vec::new(),
+
+            warp_source: [vec::Vector2f::zero(); 4],
+            warp_corners: None,
+            warp_originals: Vec::new(),
+            dragged_control: None,
+
+            deform_settings: DeformSettings::default(),
+            lattice: None,
+
+            references: Vec::new(),
+            matrix_input: Matrix3f::identity(),
+
+            registration_settings: RegistrationSettings::default(),
+            registration: None,
+            compare_target: None,
+            comparison: None,
+            crossings: RefCell::new(HashMap::new()),
+
+            exercises: exercise::builtin(),
+            exercise_index: 0,
+            exercise_shape: None,
+            exercise_log: Vec::new(),
+            exercise_score: None,
+            exercise_checkpoint: None,
+
+            stage_order: Stage::DEFAULT_ORDER,
+            explain_progress: 0.0,
+            explain_timer: None,
+
+            coords: CoordinateSystem::default(),
+        };
+        app.rebuild_index();
+
+        app
     }
 
     fn view(&self, ctx: &Context<Self>) -> Html {
@@ -124,11 +465,12 @@ impl Component for App {
                 ref={self.canvas.clone()}
                 width="800"
                 height="600"
-                style="border: 1px solid black;"
-                onmousedown={ctx.link().callback(Msg::MouseDown)}
-                onmouseup={ctx.link().callback(Msg::MouseUp)}
-                onmousemove={ctx.link().callback(Msg::MouseMove)}
-                onmouseleave={ctx.link().callback(Msg::MouseLeave)}
+                style="border: 1px solid black; touch-action: none;"
+                onpointerdown={ctx.link().callback(Msg::PointerDown)}
+                onpointerup={ctx.link().callback(Msg::PointerUp)}
+                onpointercancel={ctx.link().callback(Msg::PointerUp)}
+                onpointermove={ctx.link().callback(Msg::PointerMove)}
+                onpointerleave={ctx.link().callback(|_| Msg::PointerLeave)}
             />
         };
 
@@ -143,15 +485,33 @@ impl Component for App {
                         "Rotate" => Mode::Rotate,
                         "Scale" => Mode::Scale,
                         "Shift" => Mode::Shift,
+                        "Primitive" => Mode::Primitive,
+                        "Edit" => Mode::Edit,
+                        "Hole" => Mode::Hole,
+                        "Select" => Mode::Select,
+                        "3D" => Mode::ThreeD,
+                        "Warp" => Mode::Warp,
+                        "Lattice" => Mode::Lattice,
+                        "Exercise" => Mode::Exercise,
+                        "Explain" => Mode::Explain,
                         _ => Mode::Draw,
                     };
                     Msg::ModeChange(mode)
                 })}
             >
-                <option value="Draw">{"Draw"}</option>
-                <option value="Rotate">{"Rotate"}</option>
-                <option value="Scale">{"Scale"}</option>
-                <option value="Shift">{"Shift"}</option>
+                <option value="Draw" selected={self.mode == Mode::Draw}>{"Draw"}</option>
+                <option value="Rotate" selected={self.mode == Mode::Rotate}>{"Rotate"}</option>
+                <option value="Scale" selected={self.mode == Mode::Scale}>{"Scale"}</option>
+                <option value="Shift" selected={self.mode == Mode::Shift}>{"Shift"}</option>
+                <option value="Primitive" selected={self.mode == Mode::Primitive}>{"Primitive"}</option>
+                <option value="Edit" selected={self.mode == Mode::Edit}>{"Edit"}</option>
+                <option value="Hole" selected={self.mode == Mode::Hole}>{"Hole"}</option>
+                <option value="Select" selected={self.mode == Mode::Select}>{"Select"}</option>
+                <option value="3D" selected={self.mode == Mode::ThreeD}>{"3D"}</option>
+                <option value="Warp" selected={self.mode == Mode::Warp}>{"Warp"}</option>
+                <option value="Lattice" selected={self.mode == Mode::Lattice}>{"Lattice"}</option>
+                <option value="Exercise" selected={self.mode == Mode::Exercise}>{"Exercise"}</option>
+                <option value="Explain" selected={self.mode == Mode::Explain}>{"Explain"}</option>
             </select>
         };
 
@@ -160,7 +520,34 @@ impl Component for App {
         };
 
         let save_button = html! {
-            <button onclick={ctx.link().callback(|_| Msg::Save)}>{"Save"}</button>
+            <>
+                <button onclick={ctx.link().callback(|_| Msg::Save)}>{"Save"}</button>
+                <label>
+                    <input
+                        type="checkbox"
+                        checked={self.clip_export}
+                        onchange={ctx.link().callback(|_| Msg::ToggleClipExport)}
+                    />
+                    {"Clip to canvas"}
+                </label>
+            </>
+        };
+
+        let share_button = html! {
+            <button onclick={ctx.link().callback(|_| Msg::CopyShareLink)}>{"Copy share link"}</button>
+        };
+
+        let share_warning = match self.share_link_length {
+            Some(length) if length > share::MAX_LINK_LENGTH => html! {
+                <span style="color: red;">
+                    {format!(
+                        "Share link is {} characters long, some apps truncate links over {}",
+                        length,
+                        share::MAX_LINK_LENGTH
+                    )}
+                </span>
+            },
+            _ => html! {},
         };
 
         let finish_shape_button = html! {
@@ -174,85 +561,63 @@ impl Component for App {
             <input
                 type="file"
                 onchange={ctx.link().callback(move |e: Event| {
-                    let load_cb = load_cb.clone();
-                    let target = e.target().unwrap();
-                    let target: HtmlInputElement = target.dyn_into().unwrap();
-                    let files = target.files().unwrap();
-                    let file = files.get(0).unwrap();
-                    let reader = web_sys::FileReader::new().unwrap();
-                    let callback = Closure::wrap(Box::new(move |e: web_sys::ProgressEvent| {
-                        let target = e.target().unwrap();
-                        let target: FileReader = target.dyn_into().unwrap();
-                        let result = target.result().unwrap();
-                        let result = result.as_string().unwrap();
-                        load_cb.emit(result);
-                    }) as Box<dyn FnMut(_)>);
-                    reader.set_onload(Some(callback.as_ref().unchecked_ref()));
-                    reader.read_as_text(&file).unwrap();
-                    callback.forget();
+                    read_file_text(e, load_cb.clone());
                     Msg::None
                 })}
             />
         };
 
-        let shift_vector = self.shift_vector.clone();
-        let scale_vector = self.scale_vector.clone();
-        let pivot = self.pivot.unwrap_or(vec::Vector2f::new(0.0, 0.0));
+        let coords = self.coords.clone();
+        let size = canvas_size();
+        let shift_vector = coords.vector_to_world(self.shift_vector);
+        let scale_vector = self.scale_vector;
+        let pivot = coords.to_world(self.pivot.unwrap_or(vec::Vector2f::new(0.0, 0.0)), size);
+        let (pivot_x_coords, pivot_y_coords) = (coords.clone(), coords.clone());
+        let (shift_x_coords, shift_y_coords) = (coords.clone(), coords.clone());
+        let angle_coords = coords.clone();
         let input_boxes = html! {
             <>
                 <div>
-                    <label>{"Pivot: "}</label>
+                    <label>{format!("Pivot ({}): ", coords.unit)}</label>
                     <input
                         type="number"
-                        min="0"
-                        max="800"
-                        value={pivot.x().to_string()}
+                        value={display_number(pivot.x())}
                         oninput={ctx.link().callback(move |e: InputEvent| {
-                            let pivot = pivot.clone();
                             let target = e.target().unwrap();
                             let target: HtmlInputElement = target.dyn_into().unwrap();
                             let value = target.value_as_number() as f32;
-                            Msg::PivotChange(vec::Vector2f::new(value, pivot.y()))
+                            Msg::PivotChange(pivot_x_coords.to_screen(vec::Vector2f::new(value, pivot.y()), size))
                         })}
                     />
                     <input
                         type="number"
-                        min="0"
-                        max="600"
-                        value={pivot.y().to_string()}
+                        value={display_number(pivot.y())}
                         oninput={ctx.link().callback(move |e: InputEvent| {
-                            let pivot = pivot.clone();
                             let target = e.target().unwrap();
                             let target: HtmlInputElement = target.dyn_into().unwrap();
                             let value = target.value_as_number() as f32;
-                            Msg::PivotChange(vec::Vector2f::new(pivot.x(), value))
+                            Msg::PivotChange(pivot_y_coords.to_screen(vec::Vector2f::new(pivot.x(), value), size))
                         })}
                     />
                 </div>
                 <div>
-                    <label>{"Shift vector: "}</label>
+                    <label>{format!("Shift vector ({}): ", coords.unit)}</label>
                     <input
                         type="number"
-                        min="-1000"
-                        max="1000"
-                        value={self.shift_vector.x().to_string()}
+                        value={display_number(shift_vector.x())}
                         oninput={ctx.link().callback(move |e: InputEvent| {
-                            let shift_vector = shift_vector.clone();
                             let target: HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
                             let value = target.value_as_number() as f32;
-                            Msg::ShiftVectorChange(vec::Vector2f::new(value, shift_vector.y()))
+                            Msg::ShiftVectorChange(shift_x_coords.vector_to_screen(vec::Vector2f::new(value, shift_vector.y())))
                         })}
                     />
                     <input
                         type="number"
-                        min="-1000"
-                        max="1000"
-                        value={self.shift_vector.y().to_string()}
+                        value={display_number(shift_vector.y())}
                         oninput={ctx.link().callback(move |e: InputEvent| {
-                            let shift_vector = shift_vector.clone();
                             let target: HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
                             let value = target.value_as_number() as f32;
-                            Msg::ShiftVectorChange(vec::Vector2f::new(shift_vector.x(), value))
+                            Msg::ShiftVectorChange(shift_y_coords.vector_to_screen(vec::Vector2f::new(shift_vector.x(), value)))
                         })}
                     />
                 </div>
@@ -265,7 +630,6 @@ impl Component for App {
                         max="1000"
                         value={self.scale_vector.x().to_string()}
                         oninput={ctx.link().callback(move |e: InputEvent| {
-                            let scale_vector = scale_vector.clone();
                             let target: HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
                             let value = target.value_as_number() as f32;
                             Msg::ScaleVectorChange(vec::Vector2f::new(value, scale_vector.y()))
@@ -278,7 +642,6 @@ impl Component for App {
                         max="1000"
                         value={self.scale_vector.y().to_string()}
                         oninput={ctx.link().callback(move |e: InputEvent| {
-                            let scale_vector = scale_vector.clone();
                             let target: HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
                             let value = target.value_as_number() as f32;
                             Msg::ScaleVectorChange(vec::Vector2f::new(scale_vector.x(), value))
@@ -292,11 +655,11 @@ impl Component for App {
                         step="0.01"
                         min="-1000"
                         max="1000"
-                        value={self.rotate_angle.to_string()}
+                        value={display_number(coords.angle_to_world(self.rotate_angle))}
                         oninput={ctx.link().callback(move |e: InputEvent| {
                             let target: HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
                             let value = target.value_as_number() as f32;
-                            Msg::RotateAngleChange(value)
+                            Msg::RotateAngleChange(angle_coords.angle_to_screen(value))
                         })}
                     />
                 </div>
@@ -304,259 +667,787 @@ impl Component for App {
             </>
         };
 
-        html! {
-            <div>
-                <div>
-                    {mode_select}
-                    {clear_button}
-                    {save_button}
-                    {load_button}
-                    {finish_shape_button}
-                </div>
+        let primitive_panel = if self.mode == Mode::Primitive {
+            let settings = self.primitive_settings.clone();
+            let number_input = |label: &str,
+                                step: &str,
+                                value: f32,
+                                apply: fn(&mut PrimitiveSettings, f32)| {
+                let settings = settings.clone();
+                html! {
+                    <label>
+                        {label}
+                        <input
+                            type="number"
+                            step={step.to_string()}
+                            min="0"
+                            value={value.to_string()}
+                            oninput={ctx.link().callback(move |e: InputEvent| {
+                                let target: HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
+                                let mut settings = settings.clone();
+                                apply(&mut settings, target.value_as_number() as f32);
+                                Msg::PrimitiveSettingsChange(settings)
+                            })}
+                        />
+                    </label>
+                }
+            };
+
+            html! {
                 <div>
-                    {canvas}
-                    {input_boxes}
+                    <select
+                        onchange={ctx.link().batch_callback(|e: Event| {
+                            let target: HtmlSelectElement = e.target().unwrap().dyn_into().unwrap();
+                            Primitive::from_name(&target.value()).map(Msg::PrimitiveChange)
+                        })}
+                    >
+                        {for Primitive::ALL.iter().map(|p| html! {
+                            <option value={p.name()} selected={*p == self.primitive}>{p.name()}</option>
+                        })}
+                    </select>
+                    {number_input("Sides: ", "1", settings.sides as f32, |s, v| s.sides = v as u32)}
+                    {number_input("Segments: ", "1", settings.segments as f32, |s, v| s.segments = v as u32)}
+                    {number_input("Star inner ratio: ", "0.05", settings.inner_ratio, |s, v| s.inner_ratio = v)}
+                    {number_input("Corner radius (px): ", "1", settings.corner_radius, |s, v| s.corner_radius = v)}
+                    {number_input("Arrow width (px): ", "1", settings.arrow_width, |s, v| s.arrow_width = v)}
                 </div>
-            </div>
-        }
-    }
-
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
-        match msg {
-            Msg::MouseDown(event) => {
-                let mouse_pos =
-                    vec::Vector2f::new(event.offset_x() as f32, event.offset_y() as f32);
-
-                if self.shift_is_down {
-                    self.pivot = Some(mouse_pos);
+            }
+        } else {
+            html! {}
+        };
 
-                    return true;
+        let three_d_panel = if self.mode == Mode::ThreeD {
+            let camera = self.camera.clone();
+            let camera_input = |label: &str, step: &str, value: f32, apply: fn(&mut OrbitCamera, f32)| {
+                let camera = camera.clone();
+                html! {
+                    <label>
+                        {label}
+                        <input
+                            type="number"
+                            step={step.to_string()}
+                            value={value.to_string()}
+                            oninput={ctx.link().callback(move |e: InputEvent| {
+                                let target: HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
+                                let mut camera = camera.clone();
+                                apply(&mut camera, target.value_as_number() as f32);
+                                Msg::CameraChange(camera)
+                            })}
+                        />
+                    </label>
                 }
-
-                if self.ctrl_is_down {
-                    self.selected_shape = self.shapes.iter().find_map(|shape| {
-                        if shape.borrow().intersect_with_point(mouse_pos) {
-                            Some(shape.clone())
-                        } else {
-                            None
-                        }
-                    });
-                    
-                    return true;
+            };
+            let settings = self.extrusion_settings.clone();
+            let settings_input = |label: &str,
+                                  step: &str,
+                                  value: f32,
+                                  apply: fn(&mut ExtrusionSettings, f32)| {
+                let settings = settings.clone();
+                html! {
+                    <label>
+                        {label}
+                        <input
+                            type="number"
+                            step={step.to_string()}
+                            value={value.to_string()}
+                            oninput={ctx.link().callback(move |e: InputEvent| {
+                                let target: HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
+                                let mut settings = settings.clone();
+                                apply(&mut settings, target.value_as_number() as f32);
+                                Msg::ExtrusionSettingsChange(settings)
+                            })}
+                        />
+                    </label>
                 }
+            };
+            let projection_camera = camera.clone();
 
-                match self.mode {
-                    Mode::Draw => {
-                        if self.shapes.is_empty() {
-                            self.shapes.push(Rc::new(RefCell::new(Shape::new())));
-                        }
+            html! {
+                <div>
+                    <div>
+                        <select
+                            onchange={ctx.link().batch_callback(move |e: Event| {
+                                let target: HtmlSelectElement = e.target().unwrap().dyn_into().unwrap();
+                                Projection::from_name(&target.value()).map(|projection| {
+                                    Msg::CameraChange(OrbitCamera { projection, ..projection_camera.clone() })
+                                })
+                            })}
+                        >
+                            {for Projection::ALL.iter().map(|p| html! {
+                                <option value={p.name()} selected={*p == self.camera.projection}>{p.name()}</option>
+                            })}
+                        </select>
+                        {camera_input("Distance: ", "10", camera.distance, |c, v| c.distance = v.max(1.0))}
+                        {camera_input("FOV: ", "1", camera.fov, |c, v| c.fov = v.clamp(1.0, 170.0))}
+                        {settings_input("Depth (px): ", "1", settings.depth, |s, v| s.depth = v)}
+                    </div>
+                    <div>
+                        {"Rotation axis: "}
+                        {settings_input("x ", "0.1", settings.axis.x(), |s, v| s.axis[0] = v)}
+                        {settings_input("y ", "0.1", settings.axis.y(), |s, v| s.axis[1] = v)}
+                        {settings_input("z ", "0.1", settings.axis.z(), |s, v| s.axis[2] = v)}
+                        {settings_input("Angle: ", "1", settings.angle, |s, v| s.angle = v)}
+                        <button
+                            disabled={self.selection.is_empty()}
+                            onclick={ctx.link().callback(|_| Msg::ApplyRotation3d)}
+                        >
+                            {"Rotate selected"}
+                        </button>
+                        <button onclick={ctx.link().callback(|_| Msg::ResetRotation3d)}>{"Reset 3D rotations"}</button>
+                    </div>
+                </div>
+            }
+        } else {
+            html! {}
+        };
 
-                        self.shapes
-                            .last_mut()
-                            .unwrap()
-                            .borrow_mut()
-                            .add_point(mouse_pos);
-                    }
-                    Mode::Rotate | Mode::Scale => {
-                        self.mouse_pos = Some(mouse_pos);
-                    }
-                    Mode::Shift => {
-                        if self.mouse_origin.is_none() {
-                            self.mouse_origin = Some(mouse_pos);
-                        }
-                    }
+        let warp_panel = match (&self.mode, self.warp_corners) {
+            (Mode::Warp, Some(corners)) => {
+                let rows = Homography::from_points(self.warp_source, corners)
+                    .map(|h| *h.matrix().rows())
+                    .unwrap_or_default();
+                html! {
+                    <div>
+                        {"Homography:"}
+                        <table>
+                            {for rows.iter().map(|row| html! {
+                                <tr>
+                                    {for row.iter().map(|value| html! {
+                                        <td>{format!("{:.4}", value)}</td>
+                                    })}
+                                </tr>
+                            })}
+                        </table>
+                    </div>
                 }
+            }
+            _ => html! {},
+        };
 
-                self.selected_shape = self.shapes.iter().find_map(|shape| {
-                    if shape.borrow().intersect_with_point(mouse_pos) {
-                        Some(shape.clone())
-                    } else {
-                        None
-                    }
-                });
-
-                self.is_mouse_down = true;
+        let deform_panel = if self.mode == Mode::Lattice {
+            let settings = self.deform_settings.clone();
+            let settings_input = |label: &str,
+                                  step: &str,
+                                  value: f32,
+                                  apply: fn(&mut DeformSettings, f32)| {
+                let settings = settings.clone();
+                html! {
+                    <label>
+                        {label}
+                        <input
+                            type="number"
+                            step={step.to_string()}
+                            value={value.to_string()}
+                            oninput={ctx.link().callback(move |e: InputEvent| {
+                                let target: HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
+                                let mut settings = settings.clone();
+                                apply(&mut settings, target.value_as_number() as f32);
+                                Msg::DeformSettingsChange(settings)
+                            })}
+                        />
+                    </label>
+                }
+            };
+            let interpolation_settings = settings.clone();
+            let warp_settings = settings.clone();
 
-                true
+            html! {
+                <div>
+                    <div>
+                        {settings_input("Divisions: ", "1", settings.divisions as f32, |s, v| s.divisions = (v as u32).clamp(1, 8))}
+                        <select
+                            onchange={ctx.link().batch_callback(move |e: Event| {
+                                let target: HtmlSelectElement = e.target().unwrap().dyn_into().unwrap();
+                                LatticeInterpolation::from_name(&target.value()).map(|interpolation| {
+                                    Msg::DeformSettingsChange(DeformSettings { interpolation, ..interpolation_settings.clone() })
+                                })
+                            })}
+                        >
+                            {for LatticeInterpolation::ALL.iter().map(|i| html! {
+                                <option value={i.name()} selected={*i == settings.interpolation}>{i.name()}</option>
+                            })}
+                        </select>
+                        {settings_input("Max segment length (px): ", "1", settings.subdivision, |s, v| s.subdivision = v.max(0.0))}
+                    </div>
+                    <div>
+                        <select
+                            onchange={ctx.link().batch_callback(move |e: Event| {
+                                let target: HtmlSelectElement = e.target().unwrap().dyn_into().unwrap();
+                                WarpKind::from_name(&target.value()).map(|warp| {
+                                    Msg::DeformSettingsChange(DeformSettings { warp, ..warp_settings.clone() })
+                                })
+                            })}
+                        >
+                            {for WarpKind::ALL.iter().map(|k| html! {
+                                <option value={k.name()} selected={*k == settings.warp}>{k.name()}</option>
+                            })}
+                        </select>
+                        {settings_input(settings.warp.amount_label(), "0.1", settings.amount, |s, v| if v.is_finite() { s.amount = v })}
+                        <button
+                            disabled={self.selection.is_empty()}
+                            onclick={ctx.link().callback(|_| Msg::ApplyParametricWarp)}
+                        >
+                            {"Apply warp"}
+                        </button>
+                    </div>
+                </div>
             }
-            Msg::MouseUp(_) => {
-                self.is_mouse_down = false;
-                self.mouse_origin = None;
-                self.mouse_pos = None;
-                self.mouse_delta = None;
+        } else {
+            html! {}
+        };
 
-                true
+        let curve_select = html! {
+            <label>
+                {"Edges: "}
+                <select
+                    disabled={self.selection.is_empty()}
+                    onchange={ctx.link().batch_callback(|e: Event| {
+                        let target: HtmlSelectElement = e.target().unwrap().dyn_into().unwrap();
+                        CurveKind::from_name(&target.value()).map(Msg::CurveKindChange)
+                    })}
+                >
+                    <option selected=true disabled=true>{"Convert to..."}</option>
+                    {for CurveKind::ALL.iter().map(|k| html! {
+                        <option value={k.name()}>{k.name()}</option>
+                    })}
+                </select>
+            </label>
+        };
+
+        let shape_info = match self.selected_shape() {
+            Some(selected_shape) => {
+                let shape = selected_shape.borrow();
+                let fill_rule = shape.fill_rule();
+                let crossings = self.crossings(selected_shape).len();
+                let centroid = shape
+                    .centroid()
+                    .map(|c| self.coords.to_world(c, canvas_size()))
+                    .map(|c| format!("({:.1}, {:.1})", c.x(), c.y()))
+                    .unwrap_or_else(|| "-".to_string());
+                let area = self.coords.area_to_world(shape.area());
+
+                html! {
+                    <div>
+                        <label>
+                            {"Fill rule: "}
+                            <select
+                                onchange={ctx.link().callback(|e: Event| {
+                                    let target: HtmlSelectElement = e.target().unwrap().dyn_into().unwrap();
+                                    match target.value().as_str() {
+                                        "NonZero" => Msg::FillRuleChange(FillRule::NonZero),
+                                        _ => Msg::FillRuleChange(FillRule::EvenOdd),
+                                    }
+                                })}
+                            >
+                                <option value="EvenOdd" selected={fill_rule == FillRule::EvenOdd}>{"Even-odd"}</option>
+                                <option value="NonZero" selected={fill_rule == FillRule::NonZero}>{"Nonzero"}</option>
+                            </select>
+                        </label>
+                        <button onclick={ctx.link().callback(|_| Msg::ToggleClosed)}>
+                            {if shape.is_closed() { "Open shape" } else { "Close shape" }}
+                        </button>
+                        <label>
+                            <input
+                                type="checkbox"
+                                checked={self.show_convex_preview}
+                                onchange={ctx.link().callback(|_| Msg::ToggleConvexPreview)}
+                            />
+                            {"Preview convex parts"}
+                        </label>
+                        <button
+                            disabled={!shape.is_closed() || !shape.holes().is_empty()}
+                            onclick={ctx.link().callback(|_| Msg::DecomposeShape)}
+                        >
+                            {"Split into convex parts"}
+                        </button>
+                        <button
+                            disabled={!shape.is_closed() || crossings == 0}
+                            onclick={ctx.link().callback(|_| Msg::RepairShape)}
+                        >
+                            {format!("Repair self-intersections ({})", crossings)}
+                        </button>
+                        <button
+                            disabled={!shape.is_closed()}
+                            onclick={ctx.link().callback(|_| Msg::ToggleClipMask)}
+                        >
+                            {if shape.is_clip_mask() { "Unset clip mask" } else { "Use as clip mask" }}
+                        </button>
+                        <button
+                            disabled={!shape.is_clip_mask()}
+                            onclick={ctx.link().callback(|_| Msg::ApplyClipMasks)}
+                        >
+                            {"Apply clip masks"}
+                        </button>
+                        <span>{format!(" Area: {:.1} {}² Centroid: {}", area, self.coords.unit, centroid)}</span>
+                    </div>
+                }
             }
-            Msg::MouseMove(event) => {
-                let mouse_pos =
-                    vec::Vector2f::new(event.offset_x() as f32, event.offset_y() as f32);
+            None => html! {},
+        };
 
-                self.mouse_delta = match self.mouse_pos {
-                    Some(prev_pos) => Some(mouse_pos - prev_pos),
-                    _ => None,
+        let transform_panel = match self.selected_shape() {
+            Some(selected_shape) => {
+                let net = self
+                    .references
+                    .iter()
+                    .find(|(s, _)| Rc::ptr_eq(s, selected_shape))
+                    .map(|(_, reference)| {
+                        affine::fit(
+                            &reference.control_points(),
+                            &selected_shape.borrow().control_points(),
+                        )
+                    });
+                let matrix = self.matrix_input;
+                let matrix_input = |row: usize, column: usize| {
+                    html! {
+                        <input
+                            type="number"
+                            step="0.1"
+                            style="width: 5em;"
+                            value={matrix.get(row, column).to_string()}
+                            oninput={ctx.link().callback(move |e: InputEvent| {
+                                let target: HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
+                                let mut matrix = matrix;
+                                matrix.set(row, column, target.value_as_number() as f32);
+                                Msg::MatrixInputChange(matrix)
+                            })}
+                        />
+                    }
                 };
 
-                if self.is_mouse_down {
-                    self.mouse_pos = Some(mouse_pos);
-                } else {
-                    self.mouse_pos = None;
+                html! {
+                    <div>
+                        {"Net transform since reference: "}
+                        <button onclick={ctx.link().callback(|_| Msg::SetReference)}>{"Set reference"}</button>
+                        {match net {
+                            Some(Ok(net)) => decomposition_view(&self.coords.matrix_to_world(&net, canvas_size())),
+                            Some(Err(FitError::PointCountMismatch)) => html! { <div>{"Point structure changed since the reference"}</div> },
+                            Some(Err(FitError::Degenerate)) => html! { <div>{"Too few or collinear points to recover a transform"}</div> },
+                            None => html! { <div>{"No reference set"}</div> },
+                        }}
+                        <details>
+                            <summary>{format!("Decompose a matrix (translation in {})", self.coords.unit)}</summary>
+                            {for (0..2).map(|row| html! {
+                                <div>{for (0..3).map(|column| matrix_input(row, column))}</div>
+                            })}
+                            {decomposition_view(&matrix)}
+                        </details>
+                    </div>
                 }
+            }
+            None => html! {},
+        };
 
-                match self.mode {
-                    Mode::Rotate => {
-                        if let (Some(pivot), Some(mouse_delta), Some(mouse_pos)) =
-                            (self.pivot, self.mouse_delta, self.mouse_pos)
-                        {
-                            if let Some(selected_shape) = &self.selected_shape {
-                                let angle = (mouse_pos - pivot).angle()
-                                    - (mouse_pos - mouse_delta - pivot).angle();
-                                selected_shape
-                                    .borrow_mut()
-                                    .rotate_rel_to_point(angle, pivot);
-                            }
-                        }
-                    }
-                    Mode::Scale => {
-                        if let (Some(pivot), Some(mouse_delta), Some(mouse_pos)) =
-                            (self.pivot, self.mouse_delta, self.mouse_pos)
-                        {
-                            if let Some(selected_shape) = &self.selected_shape {
-                                let scale = (mouse_pos - pivot).length()
-                                    / (mouse_pos - mouse_delta - pivot).length();
-                                let scale = vec::Vector2f::new(scale, scale);
-                                selected_shape.borrow_mut().scale_rel_to_point(scale, pivot);
-                            }
-                        }
-                    }
-                    Mode::Shift => {
-                        if let Some(mouse_delta) = self.mouse_delta {
-                            if let Some(selected_shape) = &self.selected_shape {
-                                selected_shape.borrow_mut().shift(mouse_delta);
-                            }
-                        }
-                    }
-                    _ => {}
-                }
+        let coords_panel = {
+            let coords = self.coords.clone();
+            let (origin_coords, y_up_coords, scale_coords, unit_coords, axes_coords) = (
+                coords.clone(),
+                coords.clone(),
+                coords.clone(),
+                coords.clone(),
+                coords.clone(),
+            );
 
-                true
+            html! {
+                <div>
+                    {"Coordinates: origin "}
+                    <select
+                        onchange={ctx.link().batch_callback(move |e: Event| {
+                            let target: HtmlSelectElement = e.target().unwrap().dyn_into().unwrap();
+                            Origin::from_name(&target.value()).map(|origin| {
+                                Msg::CoordinateSystemChange(CoordinateSystem { origin, ..origin_coords.clone() })
+                            })
+                        })}
+                    >
+                        {for Origin::ALL.iter().map(|o| html! {
+                            <option value={o.name()} selected={*o == coords.origin}>{o.name()}</option>
+                        })}
+                    </select>
+                    <label>
+                        <input
+                            type="checkbox"
+                            checked={coords.y_up}
+                            onchange={ctx.link().callback(move |_| {
+                                Msg::CoordinateSystemChange(CoordinateSystem { y_up: !y_up_coords.y_up, ..y_up_coords.clone() })
+                            })}
+                        />
+                        {"y up"}
+                    </label>
+                    <label>
+                        {" Pixels per unit: "}
+                        <input
+                            type="number"
+                            step="0.1"
+                            min="0.01"
+                            value={coords.pixels_per_unit.to_string()}
+                            oninput={ctx.link().batch_callback(move |e: InputEvent| {
+                                let target: HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
+                                let pixels_per_unit = target.value_as_number() as f32;
+                                (pixels_per_unit > 0.0).then(|| {
+                                    Msg::CoordinateSystemChange(CoordinateSystem { pixels_per_unit, ..scale_coords.clone() })
+                                })
+                            })}
+                        />
+                    </label>
+                    <label>
+                        {" Unit: "}
+                        <input
+                            type="text"
+                            size="4"
+                            value={coords.unit.clone()}
+                            oninput={ctx.link().callback(move |e: InputEvent| {
+                                let target: HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
+                                Msg::CoordinateSystemChange(CoordinateSystem { unit: target.value(), ..unit_coords.clone() })
+                            })}
+                        />
+                    </label>
+                    <label>
+                        <input
+                            type="checkbox"
+                            checked={coords.show_axes}
+                            onchange={ctx.link().callback(move |_| {
+                                Msg::CoordinateSystemChange(CoordinateSystem { show_axes: !axes_coords.show_axes, ..axes_coords.clone() })
+                            })}
+                        />
+                        {"Show axes"}
+                    </label>
+                </div>
             }
-            Msg::MouseLeave(_) => false,
-            Msg::ModeChange(mode) => {
-                self.mode = mode;
+        };
 
-                true
-            }
-            Msg::Clear => {
-                self.shapes.clear();
+        let exercise_panel = if self.mode == Mode::Exercise {
+            let load_exercises_cb = ctx.link().callback(Msg::LoadExercises);
+            let score = match (self.exercise(), &self.exercise_score) {
+                (Some(exercise), Some((_, score))) => Some((*score, exercise.tolerance)),
+                _ => None,
+            };
 
-                true
+            html! {
+                <div>
+                    <select
+                        onchange={ctx.link().callback(|e: Event| {
+                            let target: HtmlSelectElement = e.target().unwrap().dyn_into().unwrap();
+                            Msg::SelectExercise(target.selected_index().max(0) as usize)
+                        })}
+                    >
+                        {for self.exercises.iter().enumerate().map(|(i, e)| html! {
+                            <option selected={i == self.exercise_index}>{&e.title}</option>
+                        })}
+                    </select>
+                    <button onclick={ctx.link().callback(|_| Msg::RestartExercise)}>{"Restart"}</button>
+                    <label>
+                        {"Load exercises: "}
+                        <input
+                            type="file"
+                            accept=".json"
+                            onchange={ctx.link().callback(move |e: Event| {
+                                read_file_text(e, load_exercises_cb.clone());
+                                Msg::None
+                            })}
+                        />
+                    </label>
+                    <div>{self.exercise().map(|e| e.description.clone()).unwrap_or_default()}</div>
+                    {match score {
+                        Some((score, tolerance)) => html! {
+                            <div>
+                                {format!(
+                                    "Distance to target: {:.2} {} (tolerance {:.2} {}) ",
+                                    self.coords.length_to_world(score.distance),
+                                    self.coords.unit,
+                                    self.coords.length_to_world(tolerance),
+                                    self.coords.unit,
+                                )}
+                                <b>{if score.passed { "Solved!" } else { "Not yet" }}</b>
+                            </div>
+                        },
+                        None => html! {},
+                    }}
+                    <ol>
+                        {for self.exercise_log.iter().map(|step| html! { <li>{self.world_step(step).describe()}</li> })}
+                    </ol>
+                    <button
+                        disabled={score.is_none()}
+                        onclick={ctx.link().callback(|_| Msg::CopyExerciseReport)}
+                    >
+                        {"Copy report"}
+                    </button>
+                </div>
             }
-            Msg::Save => {
-                let json = serde_json::to_string(
-                    &self
-                        .shapes
-                        .iter()
-                        .map(|s| (*s.borrow()).clone())
-                        .collect::<Vec<Shape>>(),
-                )
-                .unwrap();
-                let data_str = format!("data:text/json;charset=utf-8,{}", json);
-                let a = window()
-                    .unwrap()
-                    .document()
-                    .unwrap()
-                    .create_element("a")
-                    .unwrap();
-                a.set_attribute("href", &data_str).unwrap();
-                a.set_attribute("download", "shapes.json").unwrap();
-                a.set_attribute("style", "display: none").unwrap();
+        } else {
+            html! {}
+        };
 
-                let a = a.dyn_into::<HtmlElement>().unwrap();
-                a.click();
-                a.remove();
+        let explain_panel = if self.mode == Mode::Explain {
+            let step = self.transform_step();
+            let stages = self.stage_order.len();
 
-                false
+            html! {
+                <div>
+                    <button onclick={ctx.link().callback(|_| Msg::ExplainPlay)}>
+                        {if self.explain_timer.is_some() { "Pause" } else { "Play" }}
+                    </button>
+                    <input
+                        type="range"
+                        min="0"
+                        max={stages.to_string()}
+                        step="0.01"
+                        value={self.explain_progress.to_string()}
+                        oninput={ctx.link().callback(|e: InputEvent| {
+                            let target: HtmlInputElement = e.target().unwrap().dyn_into().unwrap();
+                            Msg::ExplainProgress(target.value_as_number() as f32)
+                        })}
+                    />
+                    <div style="display: flex; gap: 1em;">
+                        {for self.stage_order.iter().enumerate().map(|(i, stage)| html! {
+                            <div style={format!("color: {};", stage.color())}>
+                                {format!("{}. {} ", i + 1, stage.name())}
+                                <button
+                                    disabled={i == 0}
+                                    onclick={ctx.link().callback(move |_| Msg::MoveStageUp(i))}
+                                >
+                                    {"↑"}
+                                </button>
+                                {matrix_table(&self.coords.matrix_to_world(&explain::stage_matrix(&step, *stage, 1.0), canvas_size()))}
+                            </div>
+                        })}
+                        <div>
+                            {"Composite"}
+                            {decomposition_view(&self.coords.matrix_to_world(&explain::composite(&step), canvas_size()))}
+                        </div>
+                    </div>
+                </div>
             }
-            Msg::Load(json_str) => {
-                if let Ok(shapes) = serde_json::from_str::<Vec<Shape>>(&json_str) {
-                    self.shapes = shapes
-                        .iter()
-                        .map(|s| Rc::new(RefCell::new(s.clone())))
-                        .collect();
+        } else {
+            html! {}
+        };
 
-                    true
-                } else {
-                    window()
-                        .unwrap()
-                        .alert_with_message("Invalid JSON file")
-                        .unwrap();
+        let compare_panel = {
+            let target = self.compare_target().cloned();
+            let comparison = self.comparison.as_ref().map(|(_, comparison)| *comparison);
 
-                    false
-                }
+            html! {
+                <div>
+                    {"Compare with target: "}
+                    <button
+                        disabled={self.selection.is_empty()}
+                        onclick={ctx.link().callback(|_| Msg::SetCompareTarget)}
+                    >
+                        {"Set as target"}
+                    </button>
+                    <button
+                        disabled={target.is_none()}
+                        onclick={ctx.link().callback(|_| Msg::ClearCompareTarget)}
+                    >
+                        {"Clear target"}
+                    </button>
+                    {match comparison {
+                        Some(c) => html! {
+                            <span>{format!(
+                                " Hausdorff: {:.2} {} Fréchet: {:.2} {} IoU: {}",
+                                self.coords.length_to_world(c.hausdorff),
+                                self.coords.unit,
+                                self.coords.length_to_world(c.frechet),
+                                self.coords.unit,
+                                c.iou.map(|iou| format!("{:.3}", iou)).unwrap_or_else(|| "-".to_string()),
+                            )}</span>
+                        },
+                        None if target.is_some() => html! { <span>{" Select another shape to compare"}</span> },
+                        None => html! {},
+                    }}
+                </div>
             }
-            Msg::FinishShape => {
-                self.shapes.push(Rc::new(RefCell::new(Shape::new())));
+        };
 
-                true
+        let minkowski_panel = if self.selection.len() >= 2 {
+            let settings = self.registration_settings.clone();
+            let kind_settings = settings.clone();
+            let icp_settings = settings.clone();
+
+            html! {
+                <div>
+                    <div>
+                        {"Last two selected: "}
+                        <button onclick={ctx.link().callback(|_| Msg::MinkowskiSum)}>{"Minkowski sum"}</button>
+                        <button onclick={ctx.link().callback(|_| Msg::MinkowskiDifference)}>{"Minkowski difference"}</button>
+                    </div>
+                    <div>
+                        {"Align second-to-last selected onto last: "}
+                        <select
+                            onchange={ctx.link().batch_callback(move |e: Event| {
+                                let target: HtmlSelectElement = e.target().unwrap().dyn_into().unwrap();
+                                FitKind::from_name(&target.value()).map(|kind| {
+                                    Msg::RegistrationSettingsChange(RegistrationSettings { kind, ..kind_settings.clone() })
+                                })
+                            })}
+                        >
+                            {for FitKind::ALL.iter().map(|k| html! {
+                                <option value={k.name()} selected={*k == settings.kind}>{k.name()}</option>
+                            })}
+                        </select>
+                        <label>
+                            <input
+                                type="checkbox"
+                                checked={settings.icp}
+                                onchange={ctx.link().callback(move |_| {
+                                    Msg::RegistrationSettingsChange(RegistrationSettings { icp: !icp_settings.icp, ..icp_settings.clone() })
+                                })}
+                            />
+                            {"No correspondences (ICP)"}
+                        </label>
+                        <button onclick={ctx.link().callback(|_| Msg::FitRegistration)}>{"Fit"}</button>
+                        <button
+                            disabled={self.registration.is_none()}
+                            onclick={ctx.link().callback(|_| Msg::ApplyRegistration)}
+                        >
+                            {"Apply"}
+                        </button>
+                        {match &self.registration {
+                            Some((_, registration)) => html! {
+                                <>
+                                    <span>{format!(
+                                        " Residual: {:.3} {}",
+                                        self.coords.length_to_world(registration.residual),
+                                        self.coords.unit,
+                                    )}</span>
+                                    {decomposition_view(&self.coords.matrix_to_world(&registration.matrix, canvas_size()))}
+                                </>
+                            },
+                            None => html! {},
+                        }}
+                    </div>
+                </div>
             }
-            Msg::ShiftDown => {
-                self.shift_is_down = true;
+        } else {
+            html! {}
+        };
 
-                true
+        let hover_info = match (&self.hovered_shape, self.hover_pos) {
+            (Some(hovered_shape), Some(hover_pos)) => {
+                let shape = hovered_shape.borrow();
+                let coords = &self.coords;
+                let vertex = shape
+                    .nearest_vertex(hover_pos)
+                    .map(|(index, distance)| {
+                        format!("vertex {} ({:.1} {})", index, coords.length_to_world(distance), coords.unit)
+                    })
+                    .unwrap_or_default();
+                let edge = shape
+                    .nearest_edge(hover_pos)
+                    .map(|hit| {
+                        format!(
+                            ", edge {} at t={:.2} ({:.1} {})",
+                            hit.segment,
+                            hit.t,
+                            coords.length_to_world(hit.distance),
+                            coords.unit
+                        )
+                    })
+                    .unwrap_or_default();
+
+                html! {
+                    <div>
+                        {format!(
+                            "Hovered shape: distance {:.1} {}, nearest {}{}",
+                            coords.length_to_world(shape.distance_to_point(hover_pos)),
+                            coords.unit,
+                            vertex,
+                            edge
+                        )}
+                    </div>
+                }
             }
-            Msg::ShiftUp => {
-                self.shift_is_down = false;
-
-                true
-            }
-            Msg::ShiftVectorChange(vec) => {
-                self.shift_vector = vec;
-
-                true
-            }
-            Msg::ScaleVectorChange(vec) => {
-                self.scale_vector = vec;
-
-                true
+            (None, Some(hover_pos)) => {
+                let position = self.coords.to_world(hover_pos, canvas_size());
+                html! {
+                    <div>{format!("Cursor: ({:.2}, {:.2}) {}", position.x(), position.y(), self.coords.unit)}</div>
+                }
             }
-            Msg::RotateAngleChange(angle) => {
-                self.rotate_angle = angle;
+            _ => html! {},
+        };
 
-                true
-            }
-            Msg::PivotChange(vec) => {
-                self.pivot = Some(vec);
+        let keymap = self.keymap.borrow();
+        let keymap_panel = html! {
+            <details>
+                <summary>{"Key bindings"}</summary>
+                <table>
+                    {for keymap.bindings().iter().enumerate().map(|(index, (binding, action))| {
+                        let binding = if keymap.capturing() == Some(index) {
+                            "Press a key...".to_string()
+                        } else {
+                            binding.to_string()
+                        };
+                        html! {
+                            <tr>
+                                <td>{action.label()}</td>
+                                <td>{binding}</td>
+                                <td>
+                                    <button onclick={ctx.link().callback(move |_| Msg::RebindKey(index))}>
+                                        {"Rebind"}
+                                    </button>
+                                </td>
+                            </tr>
+                        }
+                    })}
+                </table>
+                <button onclick={ctx.link().callback(|_| Msg::ResetKeymap)}>{"Reset to defaults"}</button>
+            </details>
+        };
 
-                true
-            }
-            Msg::ApplyTransform => {
-                if let Some(selected_shape) = &self.selected_shape {
-                    let radians = self.rotate_angle.to_radians();
-                    selected_shape.borrow_mut().shift(self.shift_vector);
-                    selected_shape.borrow_mut().scale_rel_to_point(
-                        self.scale_vector,
-                        self.pivot.unwrap_or(vec::Vector2f::new(0.0, 0.0)),
-                    );
-                    selected_shape.borrow_mut().rotate_rel_to_point(
-                        radians,
-                        self.pivot.unwrap_or(vec::Vector2f::new(0.0, 0.0)),
-                    );
-                }
+        html! {
+            <div>
+                <div>
+                    {mode_select}
+                    {clear_button}
+                    {save_button}
+                    {load_button}
+                    {finish_shape_button}
+                    {share_button}
+                    {share_warning}
+                    {curve_select}
+                </div>
+                {coords_panel}
+                {primitive_panel}
+                {three_d_panel}
+                {warp_panel}
+                {deform_panel}
+                {exercise_panel}
+                {explain_panel}
+                <div>
+                    {canvas}
+                    {input_boxes}
+                    {hover_info}
+                    {shape_info}
+                    {transform_panel}
+                    {compare_panel}
+                    {minkowski_panel}
+                    {keymap_panel}
+                </div>
+            </div>
+        }
+    }
 
-                true
-            }
-            Msg::CtrlDown => {
-                self.ctrl_is_down = true;
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        if self.mode == Mode::Exercise && msg.edits_shapes() {
+            return false;
+        }
 
-                true
-            }
-            Msg::CtrlUp => {
-                self.ctrl_is_down = false;
+        // Hovering cannot change the shapes or the selection, so nothing derived from them is stale.
+        if matches!(msg, Msg::PointerMove(_)) && self.active_pointers.is_empty() {
+            return self.handle_message(ctx, msg);
+        }
 
-                true
+        let before = self.warp_snapshot();
+        let changed = self.handle_message(ctx, msg);
+        self.update_comparison();
+        self.update_exercise_score();
+        self.invalidate_registration();
+        if let (Some(before), Some(after)) = (before, self.warp_snapshot()) {
+            if before != after {
+                self.start_warp();
             }
-            Msg::None => false,
         }
+
+        changed
     }
 
     fn rendered(&mut self, _ctx: &Context<Self>, first_render: bool) {
@@ -576,28 +1467,75 @@ impl Component for App {
 
         ctx.clear_rect(0.0, 0.0, 800.0, 600.0);
 
-        for shape in self.shapes.iter() {
-            let shape = shape.borrow();
+        if self.mode == Mode::ThreeD {
+            self.render_3d(ctx);
+            return;
+        }
+
+        let viewport = Aabb::new(
+            vec::Vector2f::new(0.0, 0.0),
+            vec::Vector2f::new(CANVAS_WIDTH, CANVAS_HEIGHT),
+        );
+        if self.coords.show_axes {
+            self.render_axes(ctx);
+        }
+        let masks: Vec<_> = self
+            .shapes
+            .iter()
+            .filter(|s| s.borrow().is_clip_mask() && s.borrow().is_closed())
+            .collect();
+        ctx.save();
+        for mask in &masks {
+            trace_outline(ctx, &mask.borrow());
+            ctx.clip();
+        }
+
+        for index in self.index.query_rect(&viewport) {
+            let shape = self.shapes[index].borrow();
             let points = shape.get_points();
 
-            if points.len() > 2 {
+            if shape.is_clip_mask() && shape.is_closed() {
+                continue;
+            }
+
+            if points.len() > 1 {
+                let outline = shape.flatten(FLATTEN_TOLERANCE);
+
                 ctx.set_fill_style(&"black".into());
                 ctx.set_stroke_style(&"black".into());
                 ctx.begin_path();
-                ctx.move_to(points[0].x().into(), points[0].y().into());
+                ctx.move_to(outline[0].x().into(), outline[0].y().into());
 
-                for point in points.iter().skip(1) {
+                for point in outline.iter().skip(1) {
                     ctx.line_to(point.x().into(), point.y().into());
                 }
 
-                if points.len() > 2 {
-                    ctx.line_to(points[0].x().into(), points[0].y().into());
+                if shape.is_closed() {
+                    ctx.line_to(outline[0].x().into(), outline[0].y().into());
+                }
+
+                let holes = if shape.is_closed() { shape.holes().as_slice() } else { &[] };
+                for hole in holes.iter().filter(|h| !h.is_empty()) {
+                    ctx.move_to(hole[0].x().into(), hole[0].y().into());
+                    for point in hole.iter().skip(1) {
+                        ctx.line_to(point.x().into(), point.y().into());
+                    }
+                    ctx.close_path();
+                }
+
+                if shape.is_closed() {
+                    let winding_rule = match shape.fill_rule() {
+                        FillRule::EvenOdd => CanvasWindingRule::Evenodd,
+                        FillRule::NonZero => CanvasWindingRule::Nonzero,
+                    };
+                    ctx.set_fill_style(&"rgba(100, 149, 237, 0.3)".into());
+                    ctx.fill_with_canvas_winding_rule(winding_rule);
                 }
 
                 ctx.stroke();
             }
 
-            for point in points.iter() {
+            for point in points.iter().chain(shape.holes().iter().flatten()) {
                 ctx.begin_path();
                 ctx.set_fill_style(&"red".into());
                 ctx.arc(
@@ -610,6 +1548,294 @@ impl Component for App {
                 .expect("Failed to draw point");
                 ctx.fill();
             }
+
+            ctx.set_stroke_style(&"crimson".into());
+            ctx.set_line_width(2.0);
+            for crossing in self.crossings(&self.shapes[index]) {
+                let (x, y) = (crossing.point.x() as f64, crossing.point.y() as f64);
+                ctx.begin_path();
+                ctx.move_to(x - 5.0, y - 5.0);
+                ctx.line_to(x + 5.0, y + 5.0);
+                ctx.move_to(x - 5.0, y + 5.0);
+                ctx.line_to(x + 5.0, y - 5.0);
+                ctx.stroke();
+            }
+            ctx.set_line_width(1.0);
+        }
+        ctx.restore();
+
+        ctx.set_stroke_style(&"gray".into());
+        ctx.set_line_dash(&js_sys::Array::of2(&6.into(), &3.into()))
+            .unwrap();
+        for mask in &masks {
+            trace_outline(ctx, &mask.borrow());
+            ctx.stroke();
+        }
+        ctx.set_line_dash(&js_sys::Array::new()).unwrap();
+
+        if let (Mode::Explain, Some(selected_shape)) = (&self.mode, self.selected_shape()) {
+            let step = self.transform_step();
+            let shape = selected_shape.borrow();
+
+            ctx.set_line_dash(&js_sys::Array::of2(&6.into(), &4.into()))
+                .unwrap();
+            for (i, stage) in step.order.iter().enumerate() {
+                let matrix = explain::at_progress(&step, (i + 1) as f32);
+                let mut ghost = shape.clone();
+                ghost.map_points(|p| matrix.transform_point(p));
+                ctx.set_stroke_style(&stage.color().into());
+                trace_outline(ctx, &ghost);
+                ctx.stroke();
+            }
+            ctx.set_line_dash(&js_sys::Array::new()).unwrap();
+
+            let matrix = explain::at_progress(&step, self.explain_progress);
+            let mut animated = shape.clone();
+            animated.map_points(|p| matrix.transform_point(p));
+            ctx.set_stroke_style(&"black".into());
+            ctx.set_line_width(3.0);
+            trace_outline(ctx, &animated);
+            ctx.stroke();
+            ctx.set_line_width(1.0);
+        }
+
+        if let (Mode::Exercise, Some(exercise)) = (&self.mode, self.exercise()) {
+            ctx.set_fill_style(&"rgba(0, 0, 0, 0.1)".into());
+            ctx.set_stroke_style(&"gray".into());
+            ctx.set_line_dash(&js_sys::Array::of2(&4.into(), &4.into()))
+                .unwrap();
+            trace_outline(ctx, &exercise.target);
+            if exercise.target.is_closed() {
+                ctx.fill();
+            }
+            ctx.stroke();
+            ctx.set_line_dash(&js_sys::Array::new()).unwrap();
+        }
+
+        if let Some(target) = self.compare_target() {
+            ctx.set_stroke_style(&"darkorange".into());
+            ctx.set_line_dash(&js_sys::Array::of2(&2.into(), &4.into()))
+                .unwrap();
+            trace_outline(ctx, &target.borrow());
+            ctx.stroke();
+            ctx.set_line_dash(&js_sys::Array::new()).unwrap();
+        }
+
+        if let Some(hovered_shape) = &self.hovered_shape {
+            let shape = hovered_shape.borrow();
+            let outline = shape.flatten(FLATTEN_TOLERANCE);
+
+            if let Some(first) = outline.first() {
+                ctx.set_stroke_style(&"orange".into());
+                ctx.set_line_width(3.0);
+                ctx.begin_path();
+                ctx.move_to(first.x().into(), first.y().into());
+                for point in outline.iter().skip(1) {
+                    ctx.line_to(point.x().into(), point.y().into());
+                }
+                if shape.is_closed() {
+                    ctx.close_path();
+                }
+                ctx.stroke();
+                ctx.set_line_width(1.0);
+            }
+
+            if let Some(hit) = self.hover_pos.and_then(|pos| shape.nearest_edge(pos)) {
+                ctx.set_fill_style(&"orange".into());
+                ctx.begin_path();
+                ctx.arc(
+                    hit.point.x().into(),
+                    hit.point.y().into(),
+                    3.0,
+                    0.0,
+                    2.0 * std::f64::consts::PI,
+                )
+                .expect("Failed to draw nearest point");
+                ctx.fill();
+            }
+        }
+
+        for (shape, other, contact) in &self.collisions {
+            ctx.set_stroke_style(&"red".into());
+            ctx.set_line_width(2.0);
+            for shape in [shape, other] {
+                let shape = shape.borrow();
+                let outline = shape.flatten(FLATTEN_TOLERANCE);
+                let Some(first) = outline.first() else {
+                    continue;
+                };
+
+                ctx.begin_path();
+                ctx.move_to(first.x().into(), first.y().into());
+                for point in outline.iter().skip(1) {
+                    ctx.line_to(point.x().into(), point.y().into());
+                }
+                if shape.is_closed() {
+                    ctx.close_path();
+                }
+                ctx.stroke();
+            }
+            ctx.set_line_width(1.0);
+
+            let start = shape_bounds(&shape.borrow()).center();
+            let end = start + contact.mtv;
+            ctx.set_stroke_style(&"magenta".into());
+            ctx.begin_path();
+            ctx.move_to(start.x().into(), start.y().into());
+            ctx.line_to(end.x().into(), end.y().into());
+            if contact.depth > 0.0 {
+                let back = contact.mtv.normalize() * -6.0;
+                for angle in [0.5f32, -0.5] {
+                    let tip = end + back.rotate(angle);
+                    ctx.move_to(end.x().into(), end.y().into());
+                    ctx.line_to(tip.x().into(), tip.y().into());
+                }
+            }
+            ctx.stroke();
+        }
+
+        for selected_shape in &self.selection {
+            let bounds = shape_bounds(&selected_shape.borrow());
+            if bounds.is_empty() {
+                continue;
+            }
+
+            ctx.set_stroke_style(&"gray".into());
+            ctx.stroke_rect(
+                (bounds.min.x() - 2.0).into(),
+                (bounds.min.y() - 2.0).into(),
+                (bounds.max.x() - bounds.min.x() + 4.0).into(),
+                (bounds.max.y() - bounds.min.y() + 4.0).into(),
+            );
+        }
+
+        if let (Some(origin), Some(mouse_pos)) = (self.marquee_origin, self.mouse_pos) {
+            let rect = Aabb::from_corners(origin, mouse_pos);
+            ctx.set_stroke_style(&"blue".into());
+            ctx.set_fill_style(&"rgba(0, 0, 255, 0.1)".into());
+            ctx.fill_rect(
+                rect.min.x().into(),
+                rect.min.y().into(),
+                (rect.max.x() - rect.min.x()).into(),
+                (rect.max.y() - rect.min.y()).into(),
+            );
+            ctx.stroke_rect(
+                rect.min.x().into(),
+                rect.min.y().into(),
+                (rect.max.x() - rect.min.x()).into(),
+                (rect.max.y() - rect.min.y()).into(),
+            );
+        }
+
+        if let (true, Some(selected_shape)) = (self.show_convex_preview, self.selected_shape()) {
+            let shape = selected_shape.borrow();
+
+            if shape.is_closed() {
+                ctx.set_stroke_style(&"purple".into());
+                ctx.set_line_dash(&js_sys::Array::of2(&4.into(), &4.into()))
+                    .unwrap();
+                for piece in decompose::convex_decomposition(&shape.flatten(FLATTEN_TOLERANCE)) {
+                    ctx.begin_path();
+                    ctx.move_to(piece[0].x().into(), piece[0].y().into());
+                    for point in piece.iter().skip(1) {
+                        ctx.line_to(point.x().into(), point.y().into());
+                    }
+                    ctx.close_path();
+                    ctx.stroke();
+                }
+                ctx.set_line_dash(&js_sys::Array::new()).unwrap();
+            }
+        }
+
+        if let Some(selected_shape) = self.selected_shape() {
+            let shape = selected_shape.borrow();
+
+            ctx.set_stroke_style(&"green".into());
+            ctx.set_fill_style(&"green".into());
+            for i in 0..shape.segment_count() {
+                let (start, end) = shape.segment_endpoints(i);
+                let controls = shape.segment(i).control_points();
+                for (j, control) in controls.iter().enumerate() {
+                    let anchor = if j + 1 == controls.len() && controls.len() > 1 {
+                        end
+                    } else {
+                        start
+                    };
+                    ctx.begin_path();
+                    ctx.move_to(anchor.x().into(), anchor.y().into());
+                    ctx.line_to(control.x().into(), control.y().into());
+                    ctx.stroke();
+                    ctx.fill_rect(
+                        (control.x() - 4.0).into(),
+                        (control.y() - 4.0).into(),
+                        8.0,
+                        8.0,
+                    );
+                }
+            }
+        }
+
+        if let (Some(origin), Some(mouse_pos)) = (self.primitive_origin, self.mouse_pos) {
+            let preview = self.primitive.build(&self.primitive_settings, origin, mouse_pos);
+            let points = preview.get_points();
+
+            if let Some(first) = points.first() {
+                ctx.set_stroke_style(&"gray".into());
+                ctx.begin_path();
+                ctx.move_to(first.x().into(), first.y().into());
+                for point in points.iter().skip(1) {
+                    ctx.line_to(point.x().into(), point.y().into());
+                }
+                ctx.close_path();
+                ctx.stroke();
+            }
+        }
+
+        if let (Mode::Warp, Some(corners)) = (&self.mode, self.warp_corners) {
+            ctx.set_stroke_style(&"orange".into());
+            ctx.set_fill_style(&"orange".into());
+            ctx.begin_path();
+            ctx.move_to(corners[0].x().into(), corners[0].y().into());
+            for corner in corners.iter().skip(1) {
+                ctx.line_to(corner.x().into(), corner.y().into());
+            }
+            ctx.close_path();
+            ctx.stroke();
+            for corner in corners {
+                ctx.fill_rect(
+                    (corner.x() - 5.0).into(),
+                    (corner.y() - 5.0).into(),
+                    10.0,
+                    10.0,
+                );
+            }
+        }
+
+        if let (Mode::Lattice, Some(lattice)) = (&self.mode, &self.lattice) {
+            let n = lattice.divisions() + 1;
+            let points = lattice.points();
+            ctx.set_stroke_style(&"teal".into());
+            ctx.set_fill_style(&"teal".into());
+            ctx.begin_path();
+            for i in 0..n {
+                ctx.move_to(points[i * n].x().into(), points[i * n].y().into());
+                for point in &points[i * n + 1..(i + 1) * n] {
+                    ctx.line_to(point.x().into(), point.y().into());
+                }
+                ctx.move_to(points[i].x().into(), points[i].y().into());
+                for point in points.iter().skip(i + n).step_by(n) {
+                    ctx.line_to(point.x().into(), point.y().into());
+                }
+            }
+            ctx.stroke();
+            for point in points {
+                ctx.fill_rect(
+                    (point.x() - 4.0).into(),
+                    (point.y() - 4.0).into(),
+                    8.0,
+                    8.0,
+                );
+            }
         }
 
         if let Some(pivot) = self.pivot {
@@ -657,6 +1883,1833 @@ impl Component for App {
     }
 }
 
+impl App {
+    fn handle_message(&mut self, ctx: &Context<Self>, msg: Msg) -> bool {
+        match msg {
+            Msg::PointerDown(event) => {
+                let mouse_pos =
+                    vec::Vector2f::new(event.offset_x() as f32, event.offset_y() as f32);
+
+                if let Some(target) = event.target() {
+                    let _ = target
+                        .unchecked_into::<Element>()
+                        .set_pointer_capture(event.pointer_id());
+                }
+                self.active_pointers.push((event.pointer_id(), mouse_pos));
+
+                if self.active_pointers.len() > 1 {
+                    if self.draw_pointer.take() == Some(self.active_pointers[0].0) {
+                        if let Some(shape) = self.shapes.last().cloned() {
+                            shape.borrow_mut().pop_point();
+                            self.refit(&shape);
+                        }
+                    }
+
+                    self.is_mouse_down = false;
+                    self.mouse_origin = None;
+                    self.mouse_pos = None;
+                    self.mouse_delta = None;
+
+                    return true;
+                }
+
+                if self.shift_is_down {
+                    self.pivot = Some(mouse_pos);
+
+                    return true;
+                }
+
+                // Ctrl-click picks without starting a drag; only select mode makes it toggle membership.
+                if self.ctrl_is_down {
+                    let picked = self.pick(mouse_pos);
+                    match (&self.mode, picked) {
+                        (Mode::Select, Some(shape)) => {
+                            let group = self.group_of(&shape);
+                            if self.is_selected(&shape) {
+                                self.selection
+                                    .retain(|s| !group.iter().any(|member| Rc::ptr_eq(s, member)));
+                            } else {
+                                self.selection.extend(group);
+                            }
+                        }
+                        (Mode::Select, None) => {}
+                        (_, picked) => {
+                            self.selection = picked.map(|s| self.group_of(&s)).unwrap_or_default();
+                        }
+                    }
+                    self.remember_references();
+
+                    return true;
+                }
+
+                match self.mode {
+                    Mode::Exercise => return false,
+                    Mode::Explain => {}
+                    Mode::Draw => {
+                        if self.shapes.is_empty() {
+                            self.shapes.push(Rc::new(RefCell::new(Shape::new())));
+                            self.rebuild_index();
+                        }
+
+                        let shape = self.shapes.last().unwrap().clone();
+                        shape.borrow_mut().add_point(mouse_pos);
+                        self.refit(&shape);
+                        self.is_drawing = true;
+                        self.draw_pointer = Some(event.pointer_id());
+                    }
+                    Mode::Rotate | Mode::Scale => {
+                        self.mouse_pos = Some(mouse_pos);
+                    }
+                    Mode::Shift => {
+                        if self.mouse_origin.is_none() {
+                            self.mouse_origin = Some(mouse_pos);
+                        }
+                    }
+                    Mode::Primitive => {
+                        self.primitive_origin = Some(mouse_pos);
+                        self.mouse_pos = Some(mouse_pos);
+                    }
+                    Mode::Edit => {
+                        self.dragged_handle = self.handle_at(mouse_pos);
+                        if self.dragged_handle.is_some() {
+                            self.mouse_pos = Some(mouse_pos);
+                            self.is_mouse_down = true;
+
+                            return true;
+                        }
+                    }
+                    Mode::Hole => {
+                        if let Some(selected_shape) = self.selected_shape().cloned() {
+                            let mut shape = selected_shape.borrow_mut();
+                            if !self.is_drawing_hole {
+                                shape.add_hole(Vec::new());
+                                self.is_drawing_hole = true;
+                            }
+                            shape.add_hole_point(mouse_pos);
+
+                            return true;
+                        }
+                    }
+                    Mode::ThreeD => {
+                        self.mouse_pos = Some(mouse_pos);
+                        self.is_mouse_down = true;
+
+                        return true;
+                    }
+                    Mode::Warp => {
+                        let corner = self.warp_corners.and_then(|corners| {
+                            corners
+                                .iter()
+                                .position(|c| c.distance_to(mouse_pos) <= HANDLE_RADIUS)
+                        });
+                        if corner.is_some() {
+                            self.dragged_control = corner;
+                            self.mouse_pos = Some(mouse_pos);
+                            self.is_mouse_down = true;
+
+                            return true;
+                        }
+                    }
+                    Mode::Lattice => {
+                        let control = self.lattice.as_ref().and_then(|lattice| {
+                            lattice
+                                .points()
+                                .iter()
+                                .position(|p| p.distance_to(mouse_pos) <= HANDLE_RADIUS)
+                        });
+                        if control.is_some() {
+                            self.dragged_control = control;
+                            self.mouse_pos = Some(mouse_pos);
+                            self.is_mouse_down = true;
+
+                            return true;
+                        }
+                    }
+                    Mode::Select => {
+                        if self.pick(mouse_pos).is_none() {
+                            self.selection.clear();
+                            self.marquee_origin = Some(mouse_pos);
+                            self.mouse_pos = Some(mouse_pos);
+                            self.is_mouse_down = true;
+
+                            return true;
+                        }
+                    }
+                }
+
+                match self.pick(mouse_pos) {
+                    Some(shape) if self.is_selected(&shape) => {}
+                    picked => {
+                        self.selection = picked.map(|s| self.group_of(&s)).unwrap_or_default();
+                        self.remember_references();
+                        if matches!(self.mode, Mode::Warp | Mode::Lattice) {
+                            self.start_warp();
+                        }
+                    }
+                }
+
+                self.is_mouse_down = true;
+
+                true
+            }
+            Msg::PointerUp(event) => {
+                self.active_pointers
+                    .retain(|(pointer_id, _)| *pointer_id != event.pointer_id());
+                if self.draw_pointer == Some(event.pointer_id()) {
+                    self.draw_pointer = None;
+                }
+
+                self.dragged_handle = None;
+                self.dragged_control = None;
+                self.collisions.clear();
+
+                if let (Some(origin), Some(end)) = (self.marquee_origin.take(), self.mouse_pos) {
+                    let rect = Aabb::from_corners(origin, end);
+                    self.selection = self
+                        .index
+                        .query_rect(&rect)
+                        .into_iter()
+                        .filter(|i| rect.contains(&shape_bounds(&self.shapes[*i].borrow())))
+                        .map(|i| self.shapes[i].clone())
+                        .collect();
+                    self.remember_references();
+                }
+
+                if let Some(origin) = self.primitive_origin.take() {
+                    let end =
+                        vec::Vector2f::new(event.offset_x() as f32, event.offset_y() as f32);
+                    if self.is_mouse_down && origin.distance_to(end) > 1.0 {
+                        let shape = self.primitive.build(&self.primitive_settings, origin, end);
+                        self.insert_shapes(vec![shape]);
+                    }
+                }
+
+                self.is_mouse_down = false;
+                self.mouse_origin = None;
+                self.mouse_pos = None;
+                self.mouse_delta = None;
+
+                true
+            }
+            Msg::PointerMove(event) => {
+                let mouse_pos =
+                    vec::Vector2f::new(event.offset_x() as f32, event.offset_y() as f32);
+
+                self.hover_pos = Some(mouse_pos);
+                self.hovered_shape = if self.is_mouse_down {
+                    None
+                } else {
+                    self.pick(mouse_pos)
+                };
+
+                if self.active_pointers.len() == 2 && self.mode != Mode::Exercise {
+                    let previous = (self.active_pointers[0].1, self.active_pointers[1].1);
+                    for (pointer_id, pos) in self.active_pointers.iter_mut() {
+                        if *pointer_id == event.pointer_id() {
+                            *pos = mouse_pos;
+                        }
+                    }
+                    let current = (self.active_pointers[0].1, self.active_pointers[1].1);
+                    self.apply_gesture(previous, current);
+                    self.refit_selection();
+
+                    return true;
+                }
+
+                self.mouse_delta = self.mouse_pos.map(|prev_pos| mouse_pos - prev_pos);
+
+                if self.is_mouse_down {
+                    self.mouse_pos = Some(mouse_pos);
+                } else {
+                    self.mouse_pos = None;
+                }
+
+                match self.mode {
+                    Mode::Rotate => {
+                        if let (Some(pivot), Some(mouse_delta), Some(mouse_pos)) =
+                            (self.pivot, self.mouse_delta, self.mouse_pos)
+                        {
+                            let angle = (mouse_pos - pivot).angle()
+                                - (mouse_pos - mouse_delta - pivot).angle();
+                            for shape in &self.selection {
+                                shape.borrow_mut().rotate_rel_to_point(angle, pivot);
+                            }
+                            self.refit_selection();
+                        }
+                    }
+                    Mode::Scale => {
+                        if let (Some(pivot), Some(mouse_delta), Some(mouse_pos)) =
+                            (self.pivot, self.mouse_delta, self.mouse_pos)
+                        {
+                            let scale = (mouse_pos - pivot).length()
+                                / (mouse_pos - mouse_delta - pivot).length();
+                            let scale = vec::Vector2f::new(scale, scale);
+                            for shape in &self.selection {
+                                shape.borrow_mut().scale_rel_to_point(scale, pivot);
+                            }
+                            self.refit_selection();
+                        }
+                    }
+                    Mode::Shift => {
+                        if let Some(mouse_delta) = self.mouse_delta {
+                            for shape in &self.selection {
+                                shape.borrow_mut().shift(mouse_delta);
+                            }
+                            self.refit_selection();
+                            self.update_collisions();
+                        }
+                    }
+                    Mode::Edit => {
+                        if let (Some(handle), Some(mouse_pos)) =
+                            (self.dragged_handle, self.mouse_pos)
+                        {
+                            if let Some(selected_shape) = self.selected_shape().cloned() {
+                                selected_shape.borrow_mut().move_handle(handle, mouse_pos);
+                                self.refit(&selected_shape);
+                            }
+                        }
+                    }
+                    Mode::Warp => {
+                        if let (Some(corner), Some(mouse_pos)) =
+                            (self.dragged_control, self.mouse_pos)
+                        {
+                            self.apply_warp(corner, mouse_pos);
+                        }
+                    }
+                    Mode::Lattice => {
+                        if let (Some(control), Some(mouse_pos)) =
+                            (self.dragged_control, self.mouse_pos)
+                        {
+                            self.apply_lattice(control, mouse_pos);
+                        }
+                    }
+                    Mode::ThreeD => {
+                        if let Some(mouse_delta) = self.mouse_delta {
+                            self.camera
+                                .orbit(-mouse_delta.x() * 0.01, mouse_delta.y() * 0.01);
+                        }
+                    }
+                    _ => {}
+                }
+
+                true
+            }
+            Msg::PointerLeave => {
+                self.hover_pos = None;
+                self.hovered_shape = None;
+
+                true
+            }
+            Msg::ModeChange(mode) => {
+                if self.mode == Mode::Exercise && mode != Mode::Exercise {
+                    self.exercise_checkpoint = self.exercise_shape().map(|s| s.borrow().clone());
+                }
+                self.mode = mode;
+                self.stop_explain_timer();
+                self.is_drawing_hole = false;
+                self.start_warp();
+                if self.mode == Mode::Exercise {
+                    self.resume_exercise();
+                }
+
+                true
+            }
+            Msg::Clear => {
+                self.shapes.clear();
+                self.references.clear();
+                self.compare_target = None;
+                self.selection.clear();
+                self.rebuild_index();
+                self.is_drawing = false;
+
+                true
+            }
+            Msg::Save => {
+                let json = serde_json::to_string(
+                    &self.export_shapes(
+                        self.shapes
+                            .iter()
+                            .map(|s| (*s.borrow()).clone())
+                            .collect::<Vec<Shape>>(),
+                    ),
+                )
+                .unwrap();
+                let data_str = format!("data:text/json;charset=utf-8,{}", json);
+                let a = window()
+                    .unwrap()
+                    .document()
+                    .unwrap()
+                    .create_element("a")
+                    .unwrap();
+                a.set_attribute("href", &data_str).unwrap();
+                a.set_attribute("download", "shapes.json").unwrap();
+                a.set_attribute("style", "display: none").unwrap();
+
+                let a = a.dyn_into::<HtmlElement>().unwrap();
+                a.click();
+                a.remove();
+
+                false
+            }
+            Msg::Load(json_str) => {
+                if let Ok(shapes) = serde_json::from_str::<Vec<Shape>>(&json_str) {
+                    self.is_drawing = false;
+                    self.shapes = shapes
+                        .iter()
+                        .map(|s| Rc::new(RefCell::new(s.clone())))
+                        .collect();
+                    self.selection.clear();
+                    self.references.clear();
+                    self.compare_target = None;
+                    self.rebuild_index();
+
+                    true
+                } else {
+                    window()
+                        .unwrap()
+                        .alert_with_message("Invalid JSON file")
+                        .unwrap();
+
+                    false
+                }
+            }
+            Msg::FinishShape => {
+                if self.mode == Mode::Hole {
+                    self.is_drawing_hole = false;
+
+                    return true;
+                }
+
+                self.shapes.push(Rc::new(RefCell::new(Shape::new())));
+                self.rebuild_index();
+                self.is_drawing = false;
+
+                true
+            }
+            Msg::ShiftDown => {
+                self.shift_is_down = true;
+
+                true
+            }
+            Msg::ShiftUp => {
+                self.shift_is_down = false;
+
+                true
+            }
+            Msg::ShiftVectorChange(vec) => {
+                self.shift_vector = vec;
+
+                true
+            }
+            Msg::ScaleVectorChange(vec) => {
+                self.scale_vector = vec;
+
+                true
+            }
+            Msg::RotateAngleChange(angle) => {
+                self.rotate_angle = angle;
+
+                true
+            }
+            Msg::PivotChange(vec) => {
+                self.pivot = Some(vec);
+
+                true
+            }
+            Msg::ApplyTransform => {
+                if self.mode == Mode::Exercise {
+                    let Some(shape) = self.exercise_shape.clone() else {
+                        return false;
+                    };
+                    self.selection = vec![shape];
+                    self.exercise_log.push(self.transform_step());
+                }
+
+                let matrix = explain::composite(&self.transform_step());
+                for selected_shape in &self.selection {
+                    selected_shape
+                        .borrow_mut()
+                        .map_points(|p| matrix.transform_point(p));
+                }
+                self.refit_selection();
+                self.explain_progress = 0.0;
+
+                true
+            }
+            Msg::CtrlDown => {
+                self.ctrl_is_down = true;
+
+                true
+            }
+            Msg::CtrlUp => {
+                self.ctrl_is_down = false;
+
+                true
+            }
+            Msg::CopyShareLink => {
+                let shapes = self
+                    .shapes
+                    .iter()
+                    .map(|s| (*s.borrow()).clone())
+                    .collect::<Vec<Shape>>();
+                let Some(data) = share::encode(&shapes) else {
+                    window()
+                        .unwrap()
+                        .alert_with_message("Cannot share shapes with coordinates that are not finite or too far out")
+                        .unwrap();
+
+                    return false;
+                };
+                let location = window().unwrap().location();
+                let link = format!(
+                    "{}{}{}{}",
+                    location.origin().unwrap(),
+                    location.pathname().unwrap(),
+                    share::FRAGMENT_PREFIX,
+                    data
+                );
+
+                clipboard::write_text(&link, "Copy the share link:");
+                self.share_link_length = Some(link.len());
+
+                true
+            }
+            Msg::Copy => {
+                self.copy_selection();
+
+                false
+            }
+            Msg::Cut => {
+                if !self.copy_selection() {
+                    return false;
+                }
+
+                let selection = std::mem::take(&mut self.selection);
+                self.shapes
+                    .retain(|s| !selection.iter().any(|selected| Rc::ptr_eq(s, selected)));
+                self.rebuild_index();
+
+                true
+            }
+            Msg::Paste(texts) => {
+                let Some(mut shapes) = texts.iter().find_map(|text| parse_shapes(text)) else {
+                    return false;
+                };
+
+                self.paste_count += 1;
+                let bounds = shapes.iter().filter_map(|s| s.bounds()).reduce(|a, b| {
+                    (
+                        vec::Vector2f::new(a.0.x().min(b.0.x()), a.0.y().min(b.0.y())),
+                        vec::Vector2f::new(a.1.x().max(b.1.x()), a.1.y().max(b.1.y())),
+                    )
+                });
+                let offset = match (self.hover_pos, bounds) {
+                    (Some(hover_pos), Some((min, max))) => hover_pos - (min + max) / 2.0,
+                    _ => {
+                        let offset = PASTE_OFFSET * self.paste_count as f32;
+                        vec::Vector2f::new(offset, offset)
+                    }
+                };
+                for shape in shapes.iter_mut() {
+                    shape.shift(offset);
+                }
+
+                self.insert_shapes(shapes);
+
+                true
+            }
+            Msg::Duplicate => {
+                if self.selection.is_empty() {
+                    return false;
+                }
+
+                let shapes = self
+                    .selection
+                    .iter()
+                    .map(|selected_shape| {
+                        let mut shape = selected_shape.borrow().clone();
+                        shape.shift(vec::Vector2f::new(PASTE_OFFSET, PASTE_OFFSET));
+                        shape
+                    })
+                    .collect();
+                self.insert_shapes(shapes);
+
+                true
+            }
+            Msg::Nudge(offset) => {
+                if self.selection.is_empty() || self.mode == Mode::Exercise {
+                    return false;
+                }
+
+                for shape in &self.selection {
+                    shape.borrow_mut().shift(offset);
+                }
+                self.refit_selection();
+
+                true
+            }
+            Msg::DeleteShape => {
+                if self.selection.is_empty() {
+                    return false;
+                }
+
+                let selection = std::mem::take(&mut self.selection);
+                self.shapes
+                    .retain(|s| !selection.iter().any(|selected| Rc::ptr_eq(s, selected)));
+                self.rebuild_index();
+
+                true
+            }
+            Msg::CancelShape => {
+                if self.is_drawing_hole {
+                    if let Some(selected_shape) = self.selected_shape() {
+                        selected_shape.borrow_mut().pop_hole();
+                    }
+                    self.is_drawing_hole = false;
+
+                    return true;
+                }
+
+                if !self.is_drawing {
+                    return false;
+                }
+
+                if let Some(shape) = self.shapes.last().cloned() {
+                    *shape.borrow_mut() = Shape::new();
+                    self.refit(&shape);
+                }
+                self.is_drawing = false;
+
+                true
+            }
+            Msg::RebindKey(index) => {
+                self.keymap.borrow_mut().start_capture(index);
+
+                true
+            }
+            Msg::KeyCaptured(binding) => {
+                let mut keymap = self.keymap.borrow_mut();
+                if binding.key == "Escape" {
+                    keymap.cancel_capture();
+                } else {
+                    keymap.capture(binding);
+                }
+
+                true
+            }
+            Msg::ResetKeymap => {
+                self.keymap.borrow_mut().reset();
+
+                true
+            }
+            Msg::PrimitiveChange(primitive) => {
+                self.primitive = primitive;
+
+                true
+            }
+            Msg::PrimitiveSettingsChange(settings) => {
+                self.primitive_settings = settings;
+
+                true
+            }
+            Msg::CurveKindChange(kind) => {
+                let Some(selected_shape) = self.selected_shape().cloned() else {
+                    return false;
+                };
+
+                selected_shape.borrow_mut().set_curve_kind(kind);
+                self.refit(&selected_shape);
+
+                true
+            }
+            Msg::FillRuleChange(fill_rule) => {
+                let Some(selected_shape) = self.selected_shape().cloned() else {
+                    return false;
+                };
+
+                selected_shape.borrow_mut().set_fill_rule(fill_rule);
+                self.refit(&selected_shape);
+
+                true
+            }
+            Msg::ToggleClosed => {
+                let Some(selected_shape) = self.selected_shape().cloned() else {
+                    return false;
+                };
+
+                selected_shape.borrow_mut().toggle_closed();
+                self.refit(&selected_shape);
+
+                true
+            }
+            Msg::ToggleConvexPreview => {
+                self.show_convex_preview = !self.show_convex_preview;
+
+                true
+            }
+            Msg::DecomposeShape => {
+                let Some(selected_shape) = self.selected_shape().cloned() else {
+                    return false;
+                };
+
+                let shape = selected_shape.borrow().clone();
+                if !shape.is_closed() || !shape.holes().is_empty() {
+                    return false;
+                }
+
+                let pieces = decompose::convex_decomposition(&shape.flatten(FLATTEN_TOLERANCE));
+                if pieces.len() < 2 {
+                    return false;
+                }
+
+                let group = self.next_group();
+                let parts: Vec<_> = pieces
+                    .into_iter()
+                    .map(|piece| {
+                        let mut part = Shape::from_points(piece);
+                        part.set_group(Some(group));
+                        Rc::new(RefCell::new(part))
+                    })
+                    .collect();
+
+                let Some(index) = self.shapes.iter().position(|s| Rc::ptr_eq(s, &selected_shape)) else {
+                    return false;
+                };
+                self.shapes.splice(index..=index, parts.clone());
+                self.selection = parts;
+                self.remember_references();
+                self.rebuild_index();
+
+                true
+            }
+            Msg::RepairShape => {
+                let Some(selected_shape) = self.selected_shape().cloned() else {
+                    return false;
+                };
+
+                let shape = selected_shape.borrow().clone();
+                if !shape.is_closed() || self.crossings(&selected_shape).is_empty() {
+                    return false;
+                }
+
+                let Some(mut pieces) = shape.split_self_intersections() else {
+                    window()
+                        .unwrap()
+                        .alert_with_message("Too many crossings to repair this shape")
+                        .unwrap();
+                    return false;
+                };
+                if pieces.len() > 1 && shape.group().is_none() {
+                    let group = self.next_group();
+                    for piece in pieces.iter_mut() {
+                        piece.set_group(Some(group));
+                    }
+                }
+
+                let parts: Vec<_> = pieces
+                    .into_iter()
+                    .map(|piece| Rc::new(RefCell::new(piece)))
+                    .collect();
+
+                let Some(index) = self.shapes.iter().position(|s| Rc::ptr_eq(s, &selected_shape)) else {
+                    return false;
+                };
+                self.shapes.splice(index..=index, parts.clone());
+                self.selection = parts;
+                self.remember_references();
+                self.rebuild_index();
+
+                true
+            }
+            Msg::ToggleClipMask => {
+                let Some(selected_shape) = self.selected_shape() else {
+                    return false;
+                };
+
+                let mut shape = selected_shape.borrow_mut();
+                let clip_mask = !shape.is_clip_mask();
+                shape.set_clip_mask(clip_mask);
+
+                true
+            }
+            Msg::ApplyClipMasks => {
+                let is_mask = |s: &Rc<RefCell<Shape>>| s.borrow().is_clip_mask() && s.borrow().is_closed();
+                let masks: Vec<_> = self
+                    .shapes
+                    .iter()
+                    .filter(|s| is_mask(s))
+                    .map(|mask| mask.borrow().flatten(FLATTEN_TOLERANCE))
+                    .collect();
+                if masks.is_empty() {
+                    return false;
+                }
+
+                // Masks stay in the scene so they can be edited or applied again.
+                let mut next_group = self.next_group();
+                let mut clipped = Vec::new();
+                for shape in self.shapes.iter().cloned() {
+                    if is_mask(&shape) || shape.borrow().get_points().is_empty() {
+                        clipped.push(shape);
+                        continue;
+                    }
+
+                    let mut pieces = vec![shape.borrow().clone()];
+                    for mask in &masks {
+                        pieces = pieces
+                            .iter()
+                            .flat_map(|piece| clip::clip_shape(piece, mask))
+                            .collect();
+                    }
+                    if pieces.len() > 1 && pieces[0].group().is_none() {
+                        for piece in pieces.iter_mut() {
+                            piece.set_group(Some(next_group));
+                        }
+                        next_group += 1;
+                    }
+                    clipped.extend(pieces.into_iter().map(|p| Rc::new(RefCell::new(p))));
+                }
+
+                self.shapes = clipped;
+                self.selection.clear();
+                self.rebuild_index();
+
+                true
+            }
+            Msg::ToggleClipExport => {
+                self.clip_export = !self.clip_export;
+
+                true
+            }
+            Msg::MinkowskiSum | Msg::MinkowskiDifference => {
+                let [first, second] = match &self.selection[..] {
+                    [.., first, second] => [first.clone(), second.clone()],
+                    _ => return false,
+                };
+
+                let mut reference = second.borrow().clone();
+                let anchor = reference
+                    .centroid()
+                    .or_else(|| reference.bounds().map(|(min, max)| (min + max) / 2.0));
+                if let Some(anchor) = anchor {
+                    reference.shift(-anchor);
+                }
+
+                let pieces = match msg {
+                    Msg::MinkowskiSum => minkowski::sum(&first.borrow(), &reference),
+                    _ => minkowski::difference(&first.borrow(), &reference),
+                };
+                if pieces.is_empty() {
+                    return false;
+                }
+
+                let group = (pieces.len() > 1).then(|| self.next_group());
+                let shapes = pieces
+                    .into_iter()
+                    .map(|piece| {
+                        let mut shape = Shape::from_points(piece);
+                        shape.set_group(group);
+                        shape
+                    })
+                    .collect();
+                self.insert_shapes(shapes);
+
+                true
+            }
+            Msg::RegistrationSettingsChange(settings) => {
+                self.registration_settings = settings;
+                self.registration = None;
+
+                true
+            }
+            Msg::FitRegistration => {
+                let Some([source, target]) = self.registration_pair() else {
+                    return false;
+                };
+
+                let kind = self.registration_settings.kind;
+                let result = if self.registration_settings.icp {
+                    registration::icp(
+                        kind,
+                        &source.borrow().subdivided(ICP_SPACING).control_points(),
+                        &target.borrow().subdivided(ICP_SPACING).control_points(),
+                    )
+                } else {
+                    registration::fit(
+                        kind,
+                        &source.borrow().control_points(),
+                        &target.borrow().control_points(),
+                    )
+                };
+                match result {
+                    Ok(registration) => {
+                        let shapes = [source.borrow().clone(), target.borrow().clone()];
+                        self.registration = Some((shapes, registration));
+                    }
+                    Err(error) => {
+                        window()
+                            .unwrap()
+                            .alert_with_message(&format!("Cannot fit: {}", error))
+                            .unwrap();
+                        self.registration = None;
+                    }
+                }
+
+                true
+            }
+            Msg::ApplyRegistration => {
+                let (Some((_, registration)), Some([source, _])) =
+                    (self.registration.take(), self.registration_pair())
+                else {
+                    return false;
+                };
+
+                source
+                    .borrow_mut()
+                    .map_points(|p| registration.matrix.transform_point(p));
+                self.refit(&source);
+
+                true
+            }
+            Msg::CameraChange(camera) => {
+                self.camera = camera;
+
+                true
+            }
+            Msg::ExtrusionSettingsChange(settings) => {
+                self.extrusion_settings = settings;
+
+                true
+            }
+            Msg::ApplyRotation3d => {
+                if self.selection.is_empty() {
+                    return false;
+                }
+
+                let rotation = Matrix4f::rotation(
+                    self.extrusion_settings.axis,
+                    self.extrusion_settings.angle.to_radians(),
+                );
+                for shape in self.selection.clone() {
+                    let center = self.shape_mesh(&shape).center();
+                    let transform = Matrix4f::translation(center)
+                        * rotation
+                        * Matrix4f::translation(-center)
+                        * self.model_transform(&shape);
+
+                    self.model_transforms.retain(|(s, _)| !Rc::ptr_eq(s, &shape));
+                    self.model_transforms.push((shape, transform));
+                }
+
+                true
+            }
+            Msg::ResetRotation3d => {
+                self.model_transforms.clear();
+
+                true
+            }
+            Msg::LoadExercises(json) => match exercise::parse(&json) {
+                Ok(exercises) if !exercises.is_empty() => {
+                    self.exercises = exercises;
+                    self.exercise_index = 0;
+                    self.start_exercise();
+
+                    true
+                }
+                _ => {
+                    window()
+                        .unwrap()
+                        .alert_with_message("Invalid exercise file")
+                        .unwrap();
+
+                    false
+                }
+            },
+            Msg::SelectExercise(index) => {
+                self.exercise_index = index.min(self.exercises.len().saturating_sub(1));
+                self.start_exercise();
+
+                true
+            }
+            Msg::RestartExercise => {
+                self.start_exercise();
+
+                true
+            }
+            Msg::CopyExerciseReport => {
+                let (Some(exercise), Some(shape)) = (self.exercise(), self.exercise_shape()) else {
+                    return false;
+                };
+
+                let steps: Vec<TransformStep> =
+                    self.exercise_log.iter().map(|s| self.world_step(s)).collect();
+                let score = match &self.exercise_score {
+                    Some((scored, score)) if *scored == *shape.borrow() => *score,
+                    _ => exercise.score(&shape.borrow()),
+                };
+                let report = exercise::Report {
+                    exercise: &exercise.title,
+                    score,
+                    steps: &steps,
+                };
+                let json = serde_json::to_string_pretty(&report).unwrap();
+                clipboard::write_text(&json, "Copy the exercise report:");
+
+                false
+            }
+            Msg::MoveStageUp(index) => {
+                if index == 0 || index >= self.stage_order.len() {
+                    return false;
+                }
+
+                self.stage_order.swap(index - 1, index);
+
+                true
+            }
+            Msg::ExplainPlay => {
+                if self.explain_timer.is_some() {
+                    self.stop_explain_timer();
+
+                    return true;
+                }
+
+                if self.explain_progress >= self.stage_order.len() as f32 {
+                    self.explain_progress = 0.0;
+                }
+                let on_tick = ctx.link().callback(|_| Msg::ExplainTick);
+                let closure = Closure::wrap(Box::new(move || on_tick.emit(())) as Box<dyn FnMut()>);
+                let handle = window()
+                    .unwrap()
+                    .set_interval_with_callback_and_timeout_and_arguments_0(
+                        closure.as_ref().unchecked_ref(),
+                        EXPLAIN_TICK_MS,
+                    )
+                    .unwrap();
+                self.explain_timer = Some((handle, closure));
+
+                true
+            }
+            Msg::ExplainTick => {
+                let end = self.stage_order.len() as f32;
+                self.explain_progress = (self.explain_progress + EXPLAIN_STEP).min(end);
+                if self.explain_progress >= end {
+                    self.stop_explain_timer();
+                }
+
+                true
+            }
+            Msg::ExplainProgress(progress) => {
+                self.stop_explain_timer();
+                self.explain_progress = progress.clamp(0.0, self.stage_order.len() as f32);
+
+                true
+            }
+            Msg::CoordinateSystemChange(coords) => {
+                self.coords = coords;
+
+                true
+            }
+            Msg::SetCompareTarget => {
+                self.compare_target = self.selected_shape().cloned();
+
+                true
+            }
+            Msg::ClearCompareTarget => {
+                self.compare_target = None;
+
+                true
+            }
+            Msg::SetReference => {
+                let selection = self.selection.clone();
+                self.references.retain(|(s, _)| !selection.iter().any(|t| Rc::ptr_eq(s, t)));
+                self.remember_references();
+
+                true
+            }
+            Msg::MatrixInputChange(matrix) => {
+                self.matrix_input = matrix;
+
+                true
+            }
+            Msg::DeformSettingsChange(settings) => {
+                self.deform_settings = settings;
+                self.start_warp();
+
+                true
+            }
+            Msg::ApplyParametricWarp => {
+                let bounds = self.selection_bounds();
+                if bounds.is_empty() {
+                    return false;
+                }
+
+                let settings = self.deform_settings.clone();
+                for shape in &self.selection {
+                    let mut warped = shape.borrow().subdivided(settings.subdivision);
+                    warped.map_points(|p| settings.warp.apply(settings.amount, bounds.min, bounds.max, p));
+                    *shape.borrow_mut() = warped;
+                }
+                self.refit_selection();
+                self.start_warp();
+
+                true
+            }
+            Msg::None => false,
+        }
+    }
+
+    fn selected_shape(&self) -> Option<&Rc<RefCell<Shape>>> {
+        self.selection.last()
+    }
+
+    fn group_of(&self, shape: &Rc<RefCell<Shape>>) -> Vec<Rc<RefCell<Shape>>> {
+        let Some(group) = shape.borrow().group() else {
+            return vec![shape.clone()];
+        };
+
+        let mut members: Vec<_> = self
+            .shapes
+            .iter()
+            .filter(|s| !Rc::ptr_eq(s, shape) && s.borrow().group() == Some(group))
+            .cloned()
+            .collect();
+        members.push(shape.clone());
+
+        members
+    }
+
+    fn next_group(&self) -> u32 {
+        self.shapes
+            .iter()
+            .filter_map(|s| s.borrow().group())
+            .max()
+            .map_or(0, |group| group + 1)
+    }
+
+    fn is_selected(&self, shape: &Rc<RefCell<Shape>>) -> bool {
+        self.selection.iter().any(|s| Rc::ptr_eq(s, shape))
+    }
+
+    fn rebuild_index(&mut self) {
+        let bounds: Vec<Aabb> = self.shapes.iter().map(|s| shape_bounds(&s.borrow())).collect();
+        self.index = Bvh::build(&bounds);
+        self.index_of = self
+            .shapes
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (Rc::as_ptr(s), i))
+            .collect();
+        self.forget_removed();
+    }
+
+    /// Drops per-shape state kept for shapes that are no longer in the scene.
+    fn forget_removed(&mut self) {
+        let index_of = &self.index_of;
+        let in_scene = |s: &Rc<RefCell<Shape>>| index_of.contains_key(&Rc::as_ptr(s));
+        self.references.retain(|(s, _)| in_scene(s));
+        self.model_transforms.retain(|(s, _)| in_scene(s));
+        self.crossings.get_mut().retain(|_, (s, _)| in_scene(s));
+    }
+
+    fn refit(&mut self, shape: &Rc<RefCell<Shape>>) {
+        self.crossings.get_mut().remove(&Rc::as_ptr(shape));
+
+        let indexed = |app: &Self| {
+            app.index_of
+                .get(&Rc::as_ptr(shape))
+                .copied()
+                .filter(|&i| i < app.index.len() && app.shapes.get(i).is_some_and(|s| Rc::ptr_eq(s, shape)))
+        };
+
+        // Shapes added or moved since the last rebuild are missing from the map.
+        match indexed(self) {
+            Some(index) => self.index.update(index, shape_bounds(&shape.borrow())),
+            None if self.shapes.iter().any(|s| Rc::ptr_eq(s, shape)) => self.rebuild_index(),
+            None => {}
+        }
+    }
+
+    fn refit_selection(&mut self) {
+        for shape in self.selection.clone() {
+            self.refit(&shape);
+        }
+    }
+
+    /// The stage order is only reorderable in explain mode; everywhere else Apply keeps the default order.
+    fn transform_step(&self) -> TransformStep {
+        let order = match self.mode {
+            Mode::Explain => self.stage_order,
+            _ => Stage::DEFAULT_ORDER,
+        };
+
+        TransformStep {
+            shift: self.shift_vector,
+            scale: self.scale_vector,
+            rotation: self.rotate_angle,
+            pivot: self.pivot.unwrap_or(vec::Vector2f::new(0.0, 0.0)),
+            order,
+        }
+    }
+
+    /// The step as the user typed it, in the current coordinate system.
+    fn world_step(&self, step: &TransformStep) -> TransformStep {
+        TransformStep {
+            shift: self.coords.vector_to_world(step.shift),
+            rotation: self.coords.angle_to_world(step.rotation),
+            pivot: self.coords.to_world(step.pivot, canvas_size()),
+            ..*step
+        }
+    }
+
+    fn stop_explain_timer(&mut self) {
+        if let Some((handle, _)) = self.explain_timer.take() {
+            window().unwrap().clear_interval_with_handle(handle);
+        }
+    }
+
+    fn exercise(&self) -> Option<&Exercise> {
+        self.exercises.get(self.exercise_index)
+    }
+
+    /// The shape the student is transforming, if it is still on the canvas.
+    fn exercise_shape(&self) -> Option<&Rc<RefCell<Shape>>> {
+        self.exercise_shape
+            .as_ref()
+            .filter(|shape| self.shapes.iter().any(|s| Rc::ptr_eq(s, shape)))
+    }
+
+    /// Replaces the previous exercise shape with a fresh copy of the current exercise's start shape.
+    fn start_exercise(&mut self) {
+        if let Some(previous) = self.exercise_shape.take() {
+            self.shapes.retain(|s| !Rc::ptr_eq(s, &previous));
+        }
+        self.exercise_log.clear();
+        self.exercise_score = None;
+        self.exercise_checkpoint = None;
+
+        let Some(start) = self.exercise().map(|e| e.start.clone()) else {
+            self.rebuild_index();
+            return;
+        };
+        self.insert_shapes(vec![start]);
+        self.exercise_shape = self.selection.first().cloned();
+    }
+
+    /// Edits made in other modes bypass the log, so coming back to an edited or deleted exercise
+    /// shape restarts the attempt.
+    fn resume_exercise(&mut self) {
+        let checkpoint = self.exercise_checkpoint.take();
+        let Some(shape) = self.exercise_shape() else {
+            self.start_exercise();
+            return;
+        };
+
+        if checkpoint.is_some_and(|checkpoint| *shape.borrow() != checkpoint) {
+            self.start_exercise();
+            window()
+                .unwrap()
+                .alert_with_message("The exercise shape was edited outside exercise mode, so the attempt was restarted")
+                .unwrap();
+        }
+    }
+
+    /// Scoring is quadratic in the outline samples, so it only reruns when the exercise shape changed.
+    fn update_exercise_score(&mut self) {
+        if self.mode != Mode::Exercise {
+            return;
+        }
+
+        let (Some(exercise), Some(shape)) = (self.exercise(), self.exercise_shape()) else {
+            self.exercise_score = None;
+            return;
+        };
+        let shape = shape.borrow().clone();
+        if self.exercise_score.as_ref().map(|(scored, _)| scored) != Some(&shape) {
+            let score = exercise.score(&shape);
+            self.exercise_score = Some((shape, score));
+        }
+    }
+
+    /// The comparison target, if it still exists.
+    fn compare_target(&self) -> Option<&Rc<RefCell<Shape>>> {
+        self.compare_target
+            .as_ref()
+            .filter(|target| self.shapes.iter().any(|s| Rc::ptr_eq(s, target)))
+    }
+
+    /// Comparing is quadratic in the outline samples, so it only reruns when either compared shape changed.
+    fn update_comparison(&mut self) {
+        let shapes = match (self.selected_shape(), self.compare_target()) {
+            (Some(selected), Some(target)) if !Rc::ptr_eq(selected, target) => {
+                Some((selected.borrow().clone(), target.borrow().clone()))
+            }
+            _ => None,
+        };
+
+        match shapes {
+            Some(shapes) if self.comparison.as_ref().map(|(cached, _)| cached) != Some(&shapes) => {
+                let comparison = metrics::compare(&shapes.0, &shapes.1);
+                self.comparison = Some((shapes, comparison));
+            }
+            Some(_) => {}
+            None => self.comparison = None,
+        }
+    }
+
+    /// Crossings are computed on first use and kept until `refit` reports the shape changed. The entry
+    /// holds the shape so its address cannot be reused by a new shape before the entry is pruned.
+    fn crossings(&self, shape: &Rc<RefCell<Shape>>) -> Vec<Crossing> {
+        self.crossings
+            .borrow_mut()
+            .entry(Rc::as_ptr(shape))
+            .or_insert_with(|| (shape.clone(), shape.borrow().self_intersections()))
+            .1
+            .clone()
+    }
+
+    /// Registration aligns the second-to-last selected shape onto the last.
+    fn registration_pair(&self) -> Option<[Rc<RefCell<Shape>>; 2]> {
+        match &self.selection[..] {
+            [.., source, target] => Some([source.clone(), target.clone()]),
+            _ => None,
+        }
+    }
+
+    /// A fit only holds for the exact shapes it was computed from.
+    fn invalidate_registration(&mut self) {
+        let Some((fitted, _)) = &self.registration else {
+            return;
+        };
+
+        let current = self
+            .registration_pair()
+            .map(|[source, target]| [source.borrow().clone(), target.borrow().clone()]);
+        if current.as_ref() != Some(fitted) {
+            self.registration = None;
+        }
+    }
+
+    /// Snapshots selected shapes that have no reference yet, so their net transform can be shown later.
+    fn remember_references(&mut self) {
+        for shape in &self.selection {
+            if !self.references.iter().any(|(s, _)| Rc::ptr_eq(s, shape)) {
+                self.references.push((shape.clone(), shape.borrow().clone()));
+            }
+        }
+    }
+
+    fn selection_bounds(&self) -> Aabb {
+        self.selection
+            .iter()
+            .fold(Aabb::empty(), |acc, s| acc.union(&shape_bounds(&s.borrow())))
+    }
+
+    /// Sets up the warp quad or lattice over the selection, keeping copies of the shapes to deform.
+    fn start_warp(&mut self) {
+        self.warp_corners = None;
+        self.lattice = None;
+        self.warp_originals.clear();
+
+        let bounds = self.selection_bounds();
+        if bounds.is_empty() {
+            return;
+        }
+
+        let (min, max) = (bounds.min, bounds.max);
+        match self.mode {
+            Mode::Warp => {
+                self.warp_source = [
+                    min,
+                    vec::Vector2f::new(max.x(), min.y()),
+                    max,
+                    vec::Vector2f::new(min.x(), max.y()),
+                ];
+                self.warp_corners = Some(self.warp_source);
+                self.warp_originals = self
+                    .selection
+                    .iter()
+                    .map(|s| (s.clone(), s.borrow().clone()))
+                    .collect();
+            }
+            Mode::Lattice => {
+                self.lattice = Some(Lattice::new(
+                    min,
+                    max,
+                    self.deform_settings.divisions,
+                    self.deform_settings.interpolation,
+                ));
+                self.warp_originals = self
+                    .selection
+                    .iter()
+                    .map(|s| (s.clone(), s.borrow().subdivided(self.deform_settings.subdivision)))
+                    .collect();
+            }
+            _ => {}
+        }
+    }
+
+    fn refit_warped(&mut self) {
+        let shapes: Vec<_> = self.warp_originals.iter().map(|(s, _)| s.clone()).collect();
+        for shape in &shapes {
+            self.refit(shape);
+        }
+    }
+
+    /// The selection, and its geometry while a preview holds copies of it, in warp modes outside of
+    /// a drag; any change between two snapshots means the copies taken by `start_warp` are stale.
+    fn warp_snapshot(&self) -> Option<(Vec<*const RefCell<Shape>>, Vec<Shape>)> {
+        if !matches!(self.mode, Mode::Warp | Mode::Lattice) || self.dragged_control.is_some() {
+            return None;
+        }
+
+        let selection = self.selection.iter().map(Rc::as_ptr).collect();
+        let geometry = if self.warp_originals.is_empty() {
+            Vec::new()
+        } else {
+            self.selection.iter().map(|s| s.borrow().clone()).collect()
+        };
+
+        Some((selection, geometry))
+    }
+
+    fn apply_warp(&mut self, corner: usize, position: vec::Vector2f) {
+        let Some(corners) = self.warp_corners.as_mut() else {
+            return;
+        };
+
+        let previous = corners[corner];
+        corners[corner] = position;
+        let homography = Homography::from_points(self.warp_source, *corners).filter(|h| {
+            self.warp_originals.iter().all(|(_, original)| {
+                original
+                    .control_points()
+                    .iter()
+                    .all(|p| h.apply_checked(*p).is_some())
+            })
+        });
+        let Some(homography) = homography else {
+            corners[corner] = previous;
+            return;
+        };
+
+        for (shape, original) in &self.warp_originals {
+            let mut warped = original.clone();
+            warped.map_points(|p| homography.apply(p));
+            *shape.borrow_mut() = warped;
+        }
+        self.refit_warped();
+    }
+
+    fn apply_lattice(&mut self, control: usize, position: vec::Vector2f) {
+        let Some(lattice) = self.lattice.as_mut() else {
+            return;
+        };
+
+        lattice.move_point(control, position);
+        for (shape, original) in &self.warp_originals {
+            let mut deformed = original.clone();
+            deformed.map_points(|p| lattice.apply(p));
+            *shape.borrow_mut() = deformed;
+        }
+        self.refit_warped();
+    }
+
+    fn render_axes(&self, ctx: &CanvasRenderingContext2d) {
+        const TICK: f64 = 4.0;
+
+        let size = canvas_size();
+        let origin = self.coords.origin_position(size);
+        let spacing = self.coords.tick_spacing();
+        let (corner, opposite) = (
+            self.coords.to_world(vec::Vector2f::zero(), size),
+            self.coords.to_world(size, size),
+        );
+
+        ctx.set_stroke_style(&"#bbb".into());
+        ctx.set_fill_style(&"#888".into());
+        ctx.begin_path();
+        ctx.move_to(0.0, origin.y().into());
+        ctx.line_to(CANVAS_WIDTH.into(), origin.y().into());
+        ctx.move_to(origin.x().into(), 0.0);
+        ctx.line_to(origin.x().into(), CANVAS_HEIGHT.into());
+
+        let ticks = |a: f32, b: f32| {
+            let (low, high) = (a.min(b), a.max(b));
+            ((low / spacing).ceil() as i32..=(high / spacing).floor() as i32).map(move |i| i as f32 * spacing)
+        };
+        for x in ticks(corner.x(), opposite.x()).filter(|x| *x != 0.0) {
+            let p = self.coords.to_screen(vec::Vector2f::new(x, 0.0), size);
+            ctx.move_to(p.x().into(), f64::from(p.y()) - TICK);
+            ctx.line_to(p.x().into(), f64::from(p.y()) + TICK);
+            let _ = ctx.fill_text(&display_number(x), f64::from(p.x()) + 2.0, f64::from(p.y()) - 6.0);
+        }
+        for y in ticks(corner.y(), opposite.y()).filter(|y| *y != 0.0) {
+            let p = self.coords.to_screen(vec::Vector2f::new(0.0, y), size);
+            ctx.move_to(f64::from(p.x()) - TICK, p.y().into());
+            ctx.line_to(f64::from(p.x()) + TICK, p.y().into());
+            let _ = ctx.fill_text(&display_number(y), f64::from(p.x()) + 6.0, f64::from(p.y()) - 2.0);
+        }
+        ctx.stroke();
+
+        let x_end = self.coords.to_screen(vec::Vector2f::new(corner.x().max(opposite.x()), 0.0), size);
+        let y_end = self.coords.to_screen(vec::Vector2f::new(0.0, corner.y().max(opposite.y())), size);
+        let _ = ctx.fill_text("x", f64::from(x_end.x()) - 12.0, f64::from(x_end.y()) + 14.0);
+        let _ = ctx.fill_text("y", f64::from(y_end.x()) + 6.0, f64::from(y_end.y()) + if self.coords.y_up { 12.0 } else { -4.0 });
+    }
+
+    fn model_transform(&self, shape: &Rc<RefCell<Shape>>) -> Matrix4f {
+        self.model_transforms
+            .iter()
+            .find(|(s, _)| Rc::ptr_eq(s, shape))
+            .map(|(_, transform)| *transform)
+            .unwrap_or_default()
+    }
+
+    /// Extrudes a shape in model space: centered on the canvas, y up, and centered in depth.
+    fn shape_mesh(&self, shape: &Rc<RefCell<Shape>>) -> Mesh {
+        let to_model = |ring: &[vec::Vector2f]| -> Vec<vec::Vector2f> {
+            ring.iter()
+                .map(|p| vec::Vector2f::new(p.x() - CANVAS_WIDTH / 2.0, CANVAS_HEIGHT / 2.0 - p.y()))
+                .collect()
+        };
+        let depth = self.extrusion_settings.depth;
+        let flat = shape.borrow();
+
+        let mesh = if flat.is_closed() {
+            let mut mesh = Mesh::default();
+            for (outline, holes) in flat.filled_regions() {
+                let holes: Vec<_> = holes.iter().map(|hole| to_model(hole)).collect();
+                mesh.append(Mesh::extrude(&to_model(&outline), &holes, true, depth));
+            }
+            mesh
+        } else {
+            Mesh::extrude(&to_model(&flat.flatten(FLATTEN_TOLERANCE)), &[], false, depth)
+        };
+
+        mesh.transformed(
+            &(self.model_transform(shape)
+                * Matrix4f::translation(vec::Vector3f::new(0.0, 0.0, -depth / 2.0))),
+        )
+    }
+
+    fn render_3d(&self, ctx: &CanvasRenderingContext2d) {
+        let view = self.camera.view_matrix();
+        let view_projection = self.camera.projection_matrix(CANVAS_WIDTH / CANVAS_HEIGHT) * view;
+        let light = vec::Vector3f::new(0.3, 0.5, 0.8).normalize();
+
+        let mut faces = Vec::new();
+        for shape in &self.shapes {
+            let mesh = self.shape_mesh(shape);
+            let selected = self.is_selected(shape);
+
+            for face in &mesh.faces {
+                let screen: Vec<Vec<vec::Vector2f>> = std::iter::once(&face.outline)
+                    .chain(&face.holes)
+                    .map(|ring| {
+                        let clip: Vec<vec::Vector4f> = ring
+                            .iter()
+                            .map(|&i| view_projection.transform_point(mesh.vertices[i]))
+                            .collect();
+                        camera::clip_near(&clip)
+                            .iter()
+                            .map(|p| {
+                                vec::Vector2f::new(
+                                    (p.x() / p.w() + 1.0) / 2.0 * CANVAS_WIDTH,
+                                    (1.0 - p.y() / p.w()) / 2.0 * CANVAS_HEIGHT,
+                                )
+                            })
+                            .collect()
+                    })
+                    .collect();
+                if screen[0].len() < 3 {
+                    continue;
+                }
+
+                let depth = face
+                    .outline
+                    .iter()
+                    .map(|&i| view.transform_point(mesh.vertices[i]).z())
+                    .sum::<f32>()
+                    / face.outline.len() as f32;
+                let brightness = 0.3 + 0.7 * mesh.face_normal(&face.outline).dot(light).abs();
+
+                faces.push((depth, screen, brightness, selected));
+            }
+        }
+
+        faces.sort_by(|a, b| a.0.total_cmp(&b.0));
+        for (_, screen, brightness, selected) in faces {
+            let (r, g, b) = if selected { (60.0, 179.0, 113.0) } else { (100.0, 149.0, 237.0) };
+            ctx.set_fill_style(
+                &format!(
+                    "rgb({}, {}, {})",
+                    (r * brightness) as u8,
+                    (g * brightness) as u8,
+                    (b * brightness) as u8
+                )
+                .into(),
+            );
+            ctx.set_stroke_style(&"black".into());
+            ctx.begin_path();
+            for ring in screen.iter().filter(|ring| ring.len() >= 3) {
+                ctx.move_to(ring[0].x().into(), ring[0].y().into());
+                for point in ring.iter().skip(1) {
+                    ctx.line_to(point.x().into(), point.y().into());
+                }
+                ctx.close_path();
+            }
+            ctx.fill_with_canvas_winding_rule(CanvasWindingRule::Evenodd);
+            ctx.stroke();
+        }
+    }
+
+    fn update_collisions(&mut self) {
+        self.collisions.clear();
+
+        for selected_shape in &self.selection {
+            let bounds = shape_bounds(&selected_shape.borrow());
+            for index in self.index.query_rect(&bounds) {
+                let other = &self.shapes[index];
+                if self.is_selected(other) {
+                    continue;
+                }
+
+                if let Some(contact) = collision::collide(&selected_shape.borrow(), &other.borrow()) {
+                    self.collisions
+                        .push((selected_shape.clone(), other.clone(), contact));
+                }
+            }
+        }
+    }
+
+    fn pick(&self, position: vec::Vector2f) -> Option<Rc<RefCell<Shape>>> {
+        self.index
+            .query_point(position, PICK_TOLERANCE)
+            .into_iter()
+            .rev()
+            .map(|i| &self.shapes[i])
+            .find(|shape| shape.borrow().distance_to_point(position) <= PICK_TOLERANCE)
+            .cloned()
+    }
+
+    fn handle_at(&self, position: vec::Vector2f) -> Option<Handle> {
+        let selected_shape = self.selected_shape()?;
+        let shape = selected_shape.borrow();
+
+        shape
+            .handles()
+            .into_iter()
+            .rev()
+            .find(|(_, point)| point.distance_to(position) <= HANDLE_RADIUS)
+            .map(|(handle, _)| handle)
+    }
+
+    fn apply_gesture(
+        &self,
+        previous: (vec::Vector2f, vec::Vector2f),
+        current: (vec::Vector2f, vec::Vector2f),
+    ) {
+        if self.selection.is_empty() {
+            return;
+        }
+
+        let previous_span = previous.1 - previous.0;
+        let current_span = current.1 - current.0;
+        if previous_span.length() == 0.0 || current_span.length() == 0.0 {
+            return;
+        }
+
+        let center = (current.0 + current.1) / 2.0;
+        let scale = current_span.length() / previous_span.length();
+        let angle = current_span.angle() - previous_span.angle();
+
+        for selected_shape in &self.selection {
+            let mut shape = selected_shape.borrow_mut();
+            shape.scale_rel_to_point(vec::Vector2f::new(scale, scale), center);
+            shape.rotate_rel_to_point(angle, center);
+        }
+    }
+
+    fn copy_selection(&mut self) -> bool {
+        if self.selection.is_empty() {
+            return false;
+        }
+
+        let shapes = self.export_shapes(self.selection.iter().map(|s| s.borrow().clone()).collect());
+        let json = serde_json::to_string(&shapes).unwrap();
+        let svg = svg::to_svg(&shapes, CANVAS_WIDTH, CANVAS_HEIGHT);
+        self.paste_count = 0;
+
+        clipboard::write_shapes(&json, &svg);
+
+        true
+    }
+
+    fn export_shapes(&self, shapes: Vec<Shape>) -> Vec<Shape> {
+        if !self.clip_export {
+            return shapes;
+        }
+
+        let canvas = clip::rectangle(
+            vec::Vector2f::new(0.0, 0.0),
+            vec::Vector2f::new(CANVAS_WIDTH, CANVAS_HEIGHT),
+        );
+        shapes
+            .iter()
+            .flat_map(|shape| {
+                clip::clip_shape(shape, &canvas).into_iter().map(|mut piece| {
+                    piece.set_clip_mask(shape.is_clip_mask());
+                    piece
+                })
+            })
+            .collect()
+    }
+
+    /// Inserted copies keep their grouping among themselves but never join the groups they were copied from.
+    fn insert_shapes(&mut self, mut shapes: Vec<Shape>) {
+        let mut groups: Vec<(u32, u32)> = Vec::new();
+        let mut next_group = self.next_group();
+        for shape in shapes.iter_mut() {
+            let Some(group) = shape.group() else {
+                continue;
+            };
+            let fresh = match groups.iter().find(|(old, _)| *old == group) {
+                Some((_, fresh)) => *fresh,
+                None => {
+                    groups.push((group, next_group));
+                    next_group += 1;
+                    next_group - 1
+                }
+            };
+            shape.set_group(Some(fresh));
+        }
+
+        let index = match self.shapes.last() {
+            Some(last) if last.borrow().get_points().is_empty() => self.shapes.len() - 1,
+            _ => self.shapes.len(),
+        };
+
+        let shapes: Vec<_> = shapes
+            .into_iter()
+            .map(|s| Rc::new(RefCell::new(s)))
+            .collect();
+        self.selection = shapes.clone();
+        self.remember_references();
+        self.shapes.splice(index..index, shapes);
+
+        if !self.shapes.last().unwrap().borrow().get_points().is_empty() {
+            self.shapes.push(Rc::new(RefCell::new(Shape::new())));
+        }
+        self.rebuild_index();
+    }
+}
+
+fn canvas_size() -> vec::Vector2f {
+    vec::Vector2f::new(CANVAS_WIDTH, CANVAS_HEIGHT)
+}
+
+/// Rounds away the float noise left by converting between pixels and world units.
+fn display_number(value: f32) -> String {
+    ((value * 10000.0).round() / 10000.0).to_string()
+}
+
+fn read_file_text(e: Event, on_load: Callback<String>) {
+    let target = e.target().unwrap();
+    let target: HtmlInputElement = target.dyn_into().unwrap();
+    let files = target.files().unwrap();
+    let file = files.get(0).unwrap();
+    let reader = web_sys::FileReader::new().unwrap();
+    let callback = Closure::wrap(Box::new(move |e: web_sys::ProgressEvent| {
+        let target = e.target().unwrap();
+        let target: FileReader = target.dyn_into().unwrap();
+        let result = target.result().unwrap();
+        let result = result.as_string().unwrap();
+        on_load.emit(result);
+    }) as Box<dyn FnMut(_)>);
+    reader.set_onload(Some(callback.as_ref().unchecked_ref()));
+    reader.read_as_text(&file).unwrap();
+    callback.forget();
+}
+
+fn matrix_table(matrix: &Matrix3f) -> Html {
+    html! {
+        <table>
+            {for matrix.rows().iter().map(|row| html! {
+                <tr>
+                    {for row.iter().map(|value| html! {
+                        <td>{format!("{:.3}", value)}</td>
+                    })}
+                </tr>
+            })}
+        </table>
+    }
+}
+
+fn decomposition_view(matrix: &Matrix3f) -> Html {
+    let rows = matrix_table(matrix);
+
+    match Decomposition::from_matrix(matrix) {
+        Some(d) => html! {
+            <div>
+                {rows}
+                <div>{format!(
+                    "Translate ({:.1}, {:.1}), rotate {:.1}°, scale ({:.3}, {:.3}), shear {:.3}{}",
+                    d.translation.x(),
+                    d.translation.y(),
+                    d.rotation.to_degrees(),
+                    d.scale.x(),
+                    d.scale.y(),
+                    d.shear,
+                    if d.reflection { ", reflected" } else { "" },
+                )}</div>
+            </div>
+        },
+        None => html! {
+            <div>
+                {rows}
+                <div>{"Singular matrix, no decomposition"}</div>
+            </div>
+        },
+    }
+}
+
+fn trace_outline(ctx: &CanvasRenderingContext2d, shape: &Shape) {
+    let outline = shape.flatten(FLATTEN_TOLERANCE);
+
+    ctx.begin_path();
+    let Some(first) = outline.first() else {
+        return;
+    };
+    ctx.move_to(first.x().into(), first.y().into());
+    for point in outline.iter().skip(1) {
+        ctx.line_to(point.x().into(), point.y().into());
+    }
+    if shape.is_closed() {
+        ctx.close_path();
+    }
+}
+
+fn shape_bounds(shape: &Shape) -> Aabb {
+    match shape.bounds() {
+        Some((min, max)) => Aabb::new(min, max),
+        None => Aabb::empty(),
+    }
+}
+
+fn parse_shapes(text: &str) -> Option<Vec<Shape>> {
+    serde_json::from_str::<Vec<Shape>>(text)
+        .ok()
+        .or_else(|| serde_json::from_str::<Shape>(text).ok().map(|s| vec![s]))
+        .or_else(|| svg::from_svg(text))
+}
+
+fn is_text_input(event: &Event) -> bool {
+    event.target().is_some_and(|target| {
+        target.dyn_ref::<HtmlInputElement>().is_some()
+            || target.dyn_ref::<HtmlTextAreaElement>().is_some()
+            || target.dyn_ref::<HtmlSelectElement>().is_some()
+    })
+}
+
 fn main() {
     wasm_logger::init(wasm_logger::Config::default());
     yew::start_app::<App>();
@@ -0,0 +1,244 @@
+use std::ops::Mul;
+
+use num::Num;
+
+use crate::vec::{Vector, Vector2f, Vector3f, Vector4f};
+
+pub type Matrix3f = Matrix<f32, 3>;
+pub type Matrix4f = Matrix<f32, 4>;
+
+/// Row-major square matrix; vectors are treated as columns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Matrix<T: Num + Copy, const N: usize> {
+    rows: [[T; N]; N],
+}
+
+#[allow(dead_code)]
+impl<T: Num + Copy, const N: usize> Matrix<T, N> {
+    pub fn from_rows(rows: [[T; N]; N]) -> Self {
+        Self { rows }
+    }
+
+    pub fn identity() -> Self {
+        let mut rows = [[T::zero(); N]; N];
+        for (i, row) in rows.iter_mut().enumerate() {
+            row[i] = T::one();
+        }
+        Self { rows }
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> T {
+        self.rows[row][column]
+    }
+
+    pub fn set(&mut self, row: usize, column: usize, value: T) {
+        self.rows[row][column] = value;
+    }
+
+    pub fn rows(&self) -> &[[T; N]; N] {
+        &self.rows
+    }
+
+    pub fn transpose(&self) -> Self {
+        let mut rows = self.rows;
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = self.rows[j][i];
+            }
+        }
+        Self { rows }
+    }
+}
+
+impl<T: Num + Copy, const N: usize> Default for Matrix<T, N> {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl<T: Num + Copy, const N: usize> Mul<Self> for Matrix<T, N> {
+    type Output = Self;
+
+    fn mul(self, other: Self) -> Self {
+        let mut rows = [[T::zero(); N]; N];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..N).fold(T::zero(), |acc, k| acc + self.rows[i][k] * other.rows[k][j]);
+            }
+        }
+        Self { rows }
+    }
+}
+
+impl<T: Num + Copy, const N: usize> Mul<Vector<T, N>> for Matrix<T, N> {
+    type Output = Vector<T, N>;
+
+    fn mul(self, vector: Vector<T, N>) -> Vector<T, N> {
+        let mut result = Vector::<T, N>::zero();
+        for (i, row) in self.rows.iter().enumerate() {
+            result[i] = row
+                .iter()
+                .zip(vector.iter())
+                .fold(T::zero(), |acc, (a, b)| acc + *a * *b);
+        }
+        result
+    }
+}
+
+#[allow(dead_code)]
+impl Matrix<f32, 4> {
+    pub fn translation(offset: Vector3f) -> Self {
+        Self::from_rows([
+            [1.0, 0.0, 0.0, offset.x()],
+            [0.0, 1.0, 0.0, offset.y()],
+            [0.0, 0.0, 1.0, offset.z()],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn scaling(scale: Vector3f) -> Self {
+        Self::from_rows([
+            [scale.x(), 0.0, 0.0, 0.0],
+            [0.0, scale.y(), 0.0, 0.0],
+            [0.0, 0.0, scale.z(), 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    /// Rotation by `angle` radians about `axis` (Rodrigues' formula).
+    pub fn rotation(axis: Vector3f, angle: f32) -> Self {
+        if axis.length() == 0.0 {
+            return Self::identity();
+        }
+
+        let axis = axis.normalize();
+        let (x, y, z) = (axis.x(), axis.y(), axis.z());
+        let (sin, cos) = angle.sin_cos();
+        let t = 1.0 - cos;
+
+        Self::from_rows([
+            [
+                t * x * x + cos,
+                t * x * y - sin * z,
+                t * x * z + sin * y,
+                0.0,
+            ],
+            [
+                t * x * y + sin * z,
+                t * y * y + cos,
+                t * y * z - sin * x,
+                0.0,
+            ],
+            [
+                t * x * z - sin * y,
+                t * y * z + sin * x,
+                t * z * z + cos,
+                0.0,
+            ],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn orthographic(half_width: f32, half_height: f32, near: f32, far: f32) -> Self {
+        Self::from_rows([
+            [1.0 / half_width, 0.0, 0.0, 0.0],
+            [0.0, 1.0 / half_height, 0.0, 0.0],
+            [0.0, 0.0, -2.0 / (far - near), -(far + near) / (far - near)],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn perspective(fov_y: f32, aspect: f32, near: f32, far: f32) -> Self {
+        let f = 1.0 / (fov_y / 2.0).tan();
+
+        Self::from_rows([
+            [f / aspect, 0.0, 0.0, 0.0],
+            [0.0, f, 0.0, 0.0],
+            [
+                0.0,
+                0.0,
+                (far + near) / (near - far),
+                2.0 * far * near / (near - far),
+            ],
+            [0.0, 0.0, -1.0, 0.0],
+        ])
+    }
+
+    pub fn look_at(eye: Vector3f, target: Vector3f, up: Vector3f) -> Self {
+        let forward = (target - eye).normalize();
+        let right = forward.cross(up).normalize();
+        let up = right.cross(forward);
+
+        Self::from_rows([
+            [right.x(), right.y(), right.z(), -right.dot(eye)],
+            [up.x(), up.y(), up.z(), -up.dot(eye)],
+            [-forward.x(), -forward.y(), -forward.z(), forward.dot(eye)],
+            [0.0, 0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn transform_point(&self, point: Vector3f) -> Vector4f {
+        *self * Vector4f::new(point.x(), point.y(), point.z(), 1.0)
+    }
+}
+
+#[allow(dead_code)]
+impl Matrix<f32, 3> {
+    pub fn translation(offset: Vector2f) -> Self {
+        Self::from_rows([
+            [1.0, 0.0, offset.x()],
+            [0.0, 1.0, offset.y()],
+            [0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn scaling(scale: Vector2f) -> Self {
+        Self::from_rows([
+            [scale.x(), 0.0, 0.0],
+            [0.0, scale.y(), 0.0],
+            [0.0, 0.0, 1.0],
+        ])
+    }
+
+    pub fn rotation(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+
+        Self::from_rows([[cos, -sin, 0.0], [sin, cos, 0.0], [0.0, 0.0, 1.0]])
+    }
+
+    /// Applies the matrix to a 2D point in homogeneous coordinates, dividing by w.
+    pub fn transform_point(&self, point: Vector2f) -> Vector2f {
+        let p = *self * Vector3f::new(point.x(), point.y(), 1.0);
+        Vector2f::new(p.x() / p.z(), p.y() / p.z())
+    }
+}
+
+/// Solves `a * x = b` by Gaussian elimination with partial pivoting.
+pub fn solve<const N: usize>(mut a: [[f64; N]; N], mut b: [f64; N]) -> Option<[f64; N]> {
+    for column in 0..N {
+        let pivot =
+            (column..N).max_by(|&i, &j| a[i][column].abs().total_cmp(&a[j][column].abs()))?;
+        if a[pivot][column].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(column, pivot);
+        b.swap(column, pivot);
+
+        for row in column + 1..N {
+            let factor = a[row][column] / a[column][column];
+            let pivot_row = a[column];
+            for (value, pivot_value) in a[row].iter_mut().zip(pivot_row).skip(column) {
+                *value -= factor * pivot_value;
+            }
+            b[row] -= factor * b[column];
+        }
+    }
+
+    let mut x = [0.0; N];
+    for row in (0..N).rev() {
+        let sum: f64 = (row + 1..N).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - sum) / a[row][row];
+    }
+
+    Some(x)
+}
@@ -0,0 +1,168 @@
+use crate::{
+    matrix::Matrix4f,
+    vec::{Vector2f, Vector3f},
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtrusionSettings {
+    pub depth: f32,
+    pub axis: Vector3f,
+    pub angle: f32,
+}
+
+impl Default for ExtrusionSettings {
+    fn default() -> Self {
+        Self {
+            depth: 60.0,
+            axis: Vector3f::new(0.0, 1.0, 0.0),
+            angle: 30.0,
+        }
+    }
+}
+
+/// A planar polygon as indices into the mesh's vertices; only the caps of holed outlines have holes.
+#[derive(Debug, Clone, Default)]
+pub struct Face {
+    pub outline: Vec<usize>,
+    pub holes: Vec<Vec<usize>>,
+}
+
+impl Face {
+    pub fn new(outline: Vec<usize>) -> Face {
+        Face {
+            outline,
+            holes: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Mesh {
+    pub vertices: Vec<Vector3f>,
+    pub faces: Vec<Face>,
+}
+
+impl Mesh {
+    /// Extrudes an outline and its holes along +z into a prism with walls inside every hole; open
+    /// outlines only get their side walls and ignore holes.
+    pub fn extrude(outline: &[Vector2f], holes: &[Vec<Vector2f>], closed: bool, depth: f32) -> Mesh {
+        if outline.len() < 2 {
+            return Mesh::default();
+        }
+
+        let mut rings = vec![outline];
+        if closed {
+            rings.extend(holes.iter().filter(|h| h.len() > 2).map(|h| h.as_slice()));
+        }
+        let starts: Vec<usize> = rings
+            .iter()
+            .scan(0, |start, ring| {
+                let current = *start;
+                *start += ring.len();
+                Some(current)
+            })
+            .collect();
+        let total: usize = rings.iter().map(|ring| ring.len()).sum();
+
+        let mut vertices: Vec<Vector3f> = rings
+            .iter()
+            .flat_map(|ring| ring.iter().map(|p| Vector3f::new(p.x(), p.y(), 0.0)))
+            .collect();
+        vertices.extend(
+            rings
+                .iter()
+                .flat_map(|ring| ring.iter().map(|p| Vector3f::new(p.x(), p.y(), depth))),
+        );
+
+        let mut faces = Vec::new();
+        for (ring, &start) in rings.iter().zip(&starts) {
+            let n = ring.len();
+            let edges = if closed { n } else { n - 1 };
+            faces.extend((0..edges).map(|i| {
+                let (i, j) = (start + i, start + (i + 1) % n);
+                Face::new(vec![i, j, j + total, i + total])
+            }));
+        }
+
+        if closed {
+            let ring_indices = |ring: usize, offset: usize| -> Vec<usize> {
+                (starts[ring]..starts[ring] + rings[ring].len())
+                    .map(|i| i + offset)
+                    .collect()
+            };
+            let reversed = |mut indices: Vec<usize>| {
+                indices.reverse();
+                indices
+            };
+
+            faces.push(Face {
+                outline: reversed(ring_indices(0, 0)),
+                holes: (1..rings.len()).map(|r| reversed(ring_indices(r, 0))).collect(),
+            });
+            faces.push(Face {
+                outline: ring_indices(0, total),
+                holes: (1..rings.len()).map(|r| ring_indices(r, total)).collect(),
+            });
+        }
+
+        Mesh { vertices, faces }
+    }
+
+    pub fn append(&mut self, other: Mesh) {
+        let offset = self.vertices.len();
+        let shift = |ring: Vec<usize>| ring.into_iter().map(|i| i + offset).collect();
+
+        self.vertices.extend(other.vertices);
+        self.faces.extend(other.faces.into_iter().map(|face| Face {
+            outline: shift(face.outline),
+            holes: face.holes.into_iter().map(shift).collect(),
+        }));
+    }
+
+    pub fn center(&self) -> Vector3f {
+        if self.vertices.is_empty() {
+            return Vector3f::zero();
+        }
+
+        self.vertices
+            .iter()
+            .fold(Vector3f::zero(), |acc, v| acc + *v)
+            / self.vertices.len() as f32
+    }
+
+    pub fn transformed(&self, matrix: &Matrix4f) -> Mesh {
+        let vertices = self
+            .vertices
+            .iter()
+            .map(|v| {
+                let p = matrix.transform_point(*v);
+                Vector3f::new(p.x(), p.y(), p.z()) / p.w()
+            })
+            .collect();
+
+        Mesh {
+            vertices,
+            faces: self.faces.clone(),
+        }
+    }
+
+    /// Newell's method, so concave cap faces still get a sensible normal.
+    pub fn face_normal(&self, face: &[usize]) -> Vector3f {
+        let mut normal = Vector3f::zero();
+        for (i, &index) in face.iter().enumerate() {
+            let current = self.vertices[index];
+            let next = self.vertices[face[(i + 1) % face.len()]];
+            normal += Vector3f::new(
+                (current.y() - next.y()) * (current.z() + next.z()),
+                (current.z() - next.z()) * (current.x() + next.x()),
+                (current.x() - next.x()) * (current.y() + next.y()),
+            );
+        }
+
+        if normal.length() == 0.0 {
+            normal
+        } else {
+            normal.normalize()
+        }
+    }
+}
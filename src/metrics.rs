@@ -0,0 +1,130 @@
+use crate::{clip, shape::Shape, vec::Vector2f};
+
+const SAMPLE_SPACING: f32 = 2.0;
+const MAX_RING_SAMPLES: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Comparison {
+    pub hausdorff: f32,
+    pub frechet: f32,
+    /// `None` unless both shapes are closed.
+    pub iou: Option<f32>,
+}
+
+/// Distances are measured on outlines resampled every `SAMPLE_SPACING` pixels; Fréchet ignores holes.
+pub fn compare(a: &Shape, b: &Shape) -> Comparison {
+    let (sampled_a, sampled_b) = (a.subdivided(SAMPLE_SPACING), b.subdivided(SAMPLE_SPACING));
+    let closed = |s: &Shape, points: &[Vector2f]| {
+        let mut points = points.to_vec();
+        if s.is_closed() {
+            points.extend(points.first().copied());
+        }
+        points
+    };
+
+    let frechet = if a.is_closed() && b.is_closed() {
+        closed_frechet(sampled_a.get_points(), sampled_b.get_points())
+    } else {
+        discrete_frechet(
+            &closed(&sampled_a, sampled_a.get_points()),
+            &closed(&sampled_b, sampled_b.get_points()),
+        )
+    };
+
+    Comparison {
+        hausdorff: hausdorff(&sampled_a.control_points(), &sampled_b.control_points()),
+        frechet,
+        iou: intersection_over_union(a, b),
+    }
+}
+
+/// The Hausdorff part of `compare` on its own, for callers that need nothing else.
+pub fn shape_hausdorff(a: &Shape, b: &Shape) -> f32 {
+    let (sampled_a, sampled_b) = (a.subdivided(SAMPLE_SPACING), b.subdivided(SAMPLE_SPACING));
+    hausdorff(&sampled_a.control_points(), &sampled_b.control_points())
+}
+
+pub fn hausdorff(a: &[Vector2f], b: &[Vector2f]) -> f32 {
+    directed_hausdorff(a, b).max(directed_hausdorff(b, a))
+}
+
+fn directed_hausdorff(from: &[Vector2f], to: &[Vector2f]) -> f32 {
+    from.iter()
+        .map(|p| {
+            to.iter()
+                .map(|q| p.distance_to(*q))
+                .fold(f32::INFINITY, f32::min)
+        })
+        .fold(0.0, f32::max)
+}
+
+/// Discrete Fréchet distance between two polylines, walked from their first points.
+pub fn discrete_frechet(a: &[Vector2f], b: &[Vector2f]) -> f32 {
+    if a.is_empty() || b.is_empty() {
+        return f32::INFINITY;
+    }
+
+    let mut previous: Vec<f32> = Vec::with_capacity(b.len());
+    for (i, p) in a.iter().enumerate() {
+        let mut current: Vec<f32> = Vec::with_capacity(b.len());
+        for (j, q) in b.iter().enumerate() {
+            let distance = p.distance_to(*q);
+            let reachable = match (i, j) {
+                (0, 0) => 0.0,
+                (0, _) => current[j - 1],
+                (_, 0) => previous[0],
+                _ => previous[j].min(previous[j - 1]).min(current[j - 1]),
+            };
+            current.push(distance.max(reachable));
+        }
+        previous = current;
+    }
+
+    previous[b.len() - 1]
+}
+
+/// Fréchet distance between two rings, which have no natural start or winding: every start of `b` is tried
+/// in both directions. Rings are thinned to `MAX_RING_SAMPLES` points first to keep this cubic search bounded.
+pub fn closed_frechet(a: &[Vector2f], b: &[Vector2f]) -> f32 {
+    let thin = |ring: &[Vector2f]| -> Vec<Vector2f> {
+        let stride = ring.len().div_ceil(MAX_RING_SAMPLES).max(1);
+        ring.iter().step_by(stride).copied().collect()
+    };
+    let close = |mut ring: Vec<Vector2f>| {
+        ring.extend(ring.first().copied());
+        ring
+    };
+
+    let a = close(thin(a));
+    let forward = thin(b);
+    let backward: Vec<Vector2f> = forward.iter().rev().copied().collect();
+
+    [forward, backward]
+        .iter()
+        .flat_map(|ring| {
+            (0..ring.len()).map(move |start| {
+                let mut ring = ring.clone();
+                ring.rotate_left(start);
+                ring
+            })
+        })
+        .map(|ring| discrete_frechet(&a, &close(ring)))
+        .fold(f32::INFINITY, f32::min)
+}
+
+/// Intersection area over union area; the intersection is clipped against `b`'s outline minus its holes.
+pub fn intersection_over_union(a: &Shape, b: &Shape) -> Option<f32> {
+    if !a.is_closed() || !b.is_closed() {
+        return None;
+    }
+
+    let clipped_area = |ring: &[Vector2f]| -> f32 {
+        clip::clip_shape(a, ring).iter().map(|s| s.area()).sum()
+    };
+    let rings = b.rings();
+    let intersection = clipped_area(&rings[0])
+        - rings[1..].iter().map(|hole| clipped_area(hole)).sum::<f32>();
+    let union = a.area() + b.area() - intersection;
+
+    Some(if union > 0.0 { intersection / union } else { 0.0 })
+}
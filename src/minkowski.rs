@@ -0,0 +1,117 @@
+use crate::{
+    collision, decompose,
+    shape::{signed_area, Shape},
+    vec::Vector2f,
+};
+
+/// Minkowski sum of two shapes as a set of convex pieces whose union is the sum.
+pub fn sum(a: &Shape, b: &Shape) -> Vec<Vec<Vector2f>> {
+    let a_pieces = collision::convex_pieces(a);
+    let b_pieces = collision::convex_pieces(b);
+
+    let mut pieces = Vec::new();
+    for a_piece in &a_pieces {
+        for b_piece in &b_pieces {
+            let piece = convex_sum(a_piece, b_piece);
+            if piece.len() >= 3 {
+                pieces.push(piece);
+            }
+        }
+    }
+
+    pieces
+}
+
+/// Minkowski difference `a ⊖ b`, i.e. the sum of `a` with `b` reflected through the origin.
+pub fn difference(a: &Shape, b: &Shape) -> Vec<Vec<Vector2f>> {
+    let mut reflected = b.clone();
+    reflected.scale_rel_to_point(Vector2f::new(-1.0, -1.0), Vector2f::zero());
+
+    sum(a, &reflected)
+}
+
+pub fn convex_sum(a: &[Vector2f], b: &[Vector2f]) -> Vec<Vector2f> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+
+    if !decompose::is_convex(a) || !decompose::is_convex(b) {
+        let points: Vec<Vector2f> = a
+            .iter()
+            .flat_map(|p| b.iter().map(move |q| *p + *q))
+            .collect();
+        return convex_hull(&points);
+    }
+
+    let a = from_lowest(a);
+    let b = from_lowest(b);
+    let (n, m) = (a.len(), b.len());
+
+    let mut result = Vec::with_capacity(n + m);
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        result.push(a[i % n] + b[j % m]);
+
+        let cross = (a[(i + 1) % n] - a[i % n]).cross(b[(j + 1) % m] - b[j % m]);
+        if j == m || (i < n && cross > 0.0) {
+            i += 1;
+        } else if i == n || cross < 0.0 {
+            j += 1;
+        } else {
+            i += 1;
+            j += 1;
+        }
+    }
+
+    result
+}
+
+/// Andrew's monotone chain; the hull is returned counter-clockwise without collinear points.
+pub fn convex_hull(points: &[Vector2f]) -> Vec<Vector2f> {
+    let mut points = points.to_vec();
+    points.sort_by(|p, q| p.x().total_cmp(&q.x()).then(p.y().total_cmp(&q.y())));
+    if points.len() < 3 {
+        return points;
+    }
+
+    let mut hull: Vec<Vector2f> = Vec::with_capacity(points.len() * 2);
+    for pass in 0..2 {
+        let start = hull.len();
+        for point in points.iter() {
+            while hull.len() >= start + 2 {
+                let (a, b) = (hull[hull.len() - 2], hull[hull.len() - 1]);
+                if (b - a).cross(*point - a) > 0.0 {
+                    break;
+                }
+                hull.pop();
+            }
+            hull.push(*point);
+        }
+        hull.pop();
+
+        if pass == 0 {
+            points.reverse();
+        }
+    }
+
+    hull
+}
+
+fn from_lowest(ring: &[Vector2f]) -> Vec<Vector2f> {
+    let mut ring = ring.to_vec();
+    if signed_area(&ring) < 0.0 {
+        ring.reverse();
+    }
+
+    let lowest = (0..ring.len())
+        .min_by(|&i, &j| {
+            ring[i]
+                .y()
+                .total_cmp(&ring[j].y())
+                .then(ring[i].x().total_cmp(&ring[j].x()))
+        })
+        .unwrap_or(0);
+    ring.rotate_left(lowest);
+
+    ring
+}
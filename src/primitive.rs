@@ -0,0 +1,90 @@
+use crate::{shape::Shape, vec::Vector2f};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Primitive {
+    Rectangle,
+    RoundedRectangle,
+    Polygon,
+    Star,
+    Ellipse,
+    Circle,
+    Arrow,
+}
+
+impl Primitive {
+    pub const ALL: [Primitive; 7] = [
+        Primitive::Rectangle,
+        Primitive::RoundedRectangle,
+        Primitive::Polygon,
+        Primitive::Star,
+        Primitive::Ellipse,
+        Primitive::Circle,
+        Primitive::Arrow,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Primitive::Rectangle => "Rectangle",
+            Primitive::RoundedRectangle => "Rounded rectangle",
+            Primitive::Polygon => "Regular polygon",
+            Primitive::Star => "Star",
+            Primitive::Ellipse => "Ellipse",
+            Primitive::Circle => "Circle",
+            Primitive::Arrow => "Arrow",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Primitive> {
+        Primitive::ALL.into_iter().find(|p| p.name() == name)
+    }
+
+    pub fn build(&self, settings: &PrimitiveSettings, start: Vector2f, end: Vector2f) -> Shape {
+        let center = (start + end) / 2.0;
+        let half_size =
+            Vector2f::new((end.x() - start.x()).abs(), (end.y() - start.y()).abs()) / 2.0;
+        let radius = half_size.x().min(half_size.y());
+        let rotation = -std::f32::consts::FRAC_PI_2;
+
+        match self {
+            Primitive::Rectangle => Shape::rectangle(start, end),
+            Primitive::RoundedRectangle => Shape::rounded_rectangle(
+                start,
+                end,
+                settings.corner_radius,
+                (settings.segments / 4).max(1),
+            ),
+            Primitive::Polygon => Shape::regular_polygon(center, radius, settings.sides, rotation),
+            Primitive::Star => Shape::star(
+                center,
+                radius,
+                radius * settings.inner_ratio,
+                settings.sides,
+                rotation,
+            ),
+            Primitive::Ellipse => Shape::ellipse(center, half_size, settings.segments),
+            Primitive::Circle => Shape::circle(center, radius, settings.segments),
+            Primitive::Arrow => Shape::arrow(start, end, settings.arrow_width),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PrimitiveSettings {
+    pub sides: u32,
+    pub segments: u32,
+    pub inner_ratio: f32,
+    pub corner_radius: f32,
+    pub arrow_width: f32,
+}
+
+impl Default for PrimitiveSettings {
+    fn default() -> Self {
+        Self {
+            sides: 5,
+            segments: 32,
+            inner_ratio: 0.5,
+            corner_radius: 20.0,
+            arrow_width: 10.0,
+        }
+    }
+}
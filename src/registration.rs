@@ -0,0 +1,174 @@
+use crate::{
+    affine::{self, FitError},
+    matrix::Matrix3f,
+    vec::Vector2f,
+};
+
+const ICP_ITERATIONS: usize = 50;
+const ICP_CONVERGENCE: f32 = 1e-4;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FitKind {
+    Rigid,
+    Similarity,
+    Affine,
+}
+
+impl FitKind {
+    pub const ALL: [FitKind; 3] = [FitKind::Rigid, FitKind::Similarity, FitKind::Affine];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FitKind::Rigid => "Rigid",
+            FitKind::Similarity => "Similarity",
+            FitKind::Affine => "Affine",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<FitKind> {
+        FitKind::ALL.into_iter().find(|k| k.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegistrationSettings {
+    pub kind: FitKind,
+    pub icp: bool,
+}
+
+impl Default for RegistrationSettings {
+    fn default() -> Self {
+        Self {
+            kind: FitKind::Rigid,
+            icp: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Registration {
+    pub matrix: Matrix3f,
+    /// Root mean square distance between the moved source points and their matches.
+    pub residual: f32,
+}
+
+/// Best transform taking `source[i]` onto `target[i]`; rigid and similarity fits never reflect.
+pub fn fit(kind: FitKind, source: &[Vector2f], target: &[Vector2f]) -> Result<Registration, FitError> {
+    let matrix = match kind {
+        FitKind::Affine => affine::fit(source, target)?,
+        FitKind::Rigid | FitKind::Similarity => procrustes(kind, source, target)?,
+    };
+
+    Ok(Registration {
+        matrix,
+        residual: residual(&matrix, source, target),
+    })
+}
+
+/// Iterative closest point: alternately matches each source point to its nearest target point and refits.
+pub fn icp(kind: FitKind, source: &[Vector2f], target: &[Vector2f]) -> Result<Registration, FitError> {
+    if source.is_empty() || target.is_empty() {
+        return Err(FitError::Degenerate);
+    }
+
+    let offset = centroid(target) - centroid(source);
+    let mut matrix = Matrix3f::from_rows([
+        [1.0, 0.0, offset.x()],
+        [0.0, 1.0, offset.y()],
+        [0.0, 0.0, 1.0],
+    ]);
+    let mut previous = f32::INFINITY;
+
+    for _ in 0..ICP_ITERATIONS {
+        let matches: Vec<Vector2f> = source
+            .iter()
+            .map(|p| nearest(target, matrix.transform_point(*p)))
+            .collect();
+        let Ok(registration) = fit(kind, source, &matches) else {
+            break;
+        };
+
+        matrix = registration.matrix;
+        if (previous - registration.residual).abs() < ICP_CONVERGENCE {
+            break;
+        }
+        previous = registration.residual;
+    }
+
+    let matches: Vec<Vector2f> = source
+        .iter()
+        .map(|p| nearest(target, matrix.transform_point(*p)))
+        .collect();
+
+    Ok(Registration {
+        matrix,
+        residual: residual(&matrix, source, &matches),
+    })
+}
+
+pub fn residual(matrix: &Matrix3f, source: &[Vector2f], target: &[Vector2f]) -> f32 {
+    if source.is_empty() {
+        return 0.0;
+    }
+
+    let sum: f32 = source
+        .iter()
+        .zip(target)
+        .map(|(s, t)| {
+            let d = matrix.transform_point(*s) - *t;
+            d.dot(d)
+        })
+        .sum();
+
+    (sum / source.len() as f32).sqrt()
+}
+
+/// Closed-form 2D Kabsch: the rotation angle comes from the summed dot and cross products of the centered points.
+fn procrustes(kind: FitKind, source: &[Vector2f], target: &[Vector2f]) -> Result<Matrix3f, FitError> {
+    if source.len() != target.len() {
+        return Err(FitError::PointCountMismatch);
+    }
+    if source.len() < 2 {
+        return Err(FitError::Degenerate);
+    }
+
+    let (source_center, target_center) = (centroid(source), centroid(target));
+    let (mut dot, mut cross, mut spread) = (0.0, 0.0, 0.0);
+    for (s, t) in source.iter().zip(target) {
+        let (s, t) = (*s - source_center, *t - target_center);
+        dot += s.dot(t);
+        cross += s.cross(t);
+        spread += s.dot(s);
+    }
+
+    let angle = cross.atan2(dot);
+    let (sin, cos) = angle.sin_cos();
+    let scale = match kind {
+        FitKind::Similarity if spread > f32::EPSILON => (dot * cos + cross * sin) / spread,
+        _ => 1.0,
+    };
+
+    let rotated = Vector2f::new(
+        scale * (cos * source_center.x() - sin * source_center.y()),
+        scale * (sin * source_center.x() + cos * source_center.y()),
+    );
+    let translation = target_center - rotated;
+
+    Ok(Matrix3f::from_rows([
+        [scale * cos, -scale * sin, translation.x()],
+        [scale * sin, scale * cos, translation.y()],
+        [0.0, 0.0, 1.0],
+    ]))
+}
+
+fn centroid(points: &[Vector2f]) -> Vector2f {
+    points.iter().fold(Vector2f::zero(), |acc, p| acc + *p) / points.len().max(1) as f32
+}
+
+fn nearest(points: &[Vector2f], point: Vector2f) -> Vector2f {
+    points
+        .iter()
+        .copied()
+        .min_by(|a, b| a.distance_to(point).total_cmp(&b.distance_to(point)))
+        .unwrap_or(point)
+}
@@ -1,36 +1,601 @@
 use serde::{Serialize, Deserialize};
 
-use crate::vec::Vector2f;
+use crate::{
+    curve::{self, CurveKind, Segment},
+    simplify::{self, Crossing},
+    vec::Vector2f,
+};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+pub const FLATTEN_TOLERANCE: f32 = 0.25;
+pub const OPEN_HIT_TOLERANCE: f32 = 5.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Handle {
+    Vertex(usize),
+    Control(usize, usize),
+    HoleVertex(usize, usize),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct EdgeHit {
+    pub segment: usize,
+    pub t: f32,
+    pub point: Vector2f,
+    pub distance: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub enum FillRule {
+    #[default]
+    EvenOdd,
+    NonZero,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Shape {
     points: Vec<Vector2f>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    segments: Vec<Segment>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    holes: Vec<Vec<Vector2f>>,
+    #[serde(default)]
+    fill_rule: FillRule,
+    #[serde(default = "default_closed")]
+    closed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    group: Option<u32>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    clip_mask: bool,
+}
+
+fn default_closed() -> bool {
+    true
 }
 
 impl Shape {
     pub fn new() -> Shape {
+        Shape::from_points(Vec::new())
+    }
+
+    pub fn from_points(points: Vec<Vector2f>) -> Shape {
         Shape {
-            points: Vec::new(),
+            points,
+            segments: Vec::new(),
+            holes: Vec::new(),
+            fill_rule: FillRule::default(),
+            closed: true,
+            group: None,
+            clip_mask: false,
+        }
+    }
+
+    pub fn rectangle(corner: Vector2f, opposite: Vector2f) -> Shape {
+        let (min, max) = normalized_box(corner, opposite);
+
+        Shape::from_points(vec![
+            min,
+            Vector2f::new(max.x(), min.y()),
+            max,
+            Vector2f::new(min.x(), max.y()),
+        ])
+    }
+
+    pub fn regular_polygon(center: Vector2f, radius: f32, sides: u32, rotation: f32) -> Shape {
+        let sides = sides.max(3);
+        let step = std::f32::consts::TAU / sides as f32;
+
+        Shape::from_points(
+            (0..sides)
+                .map(|i| center + Vector2f::new(radius, 0.0).rotate(rotation + step * i as f32))
+                .collect(),
+        )
+    }
+
+    pub fn star(
+        center: Vector2f,
+        outer_radius: f32,
+        inner_radius: f32,
+        tips: u32,
+        rotation: f32,
+    ) -> Shape {
+        let tips = tips.max(2);
+        let step = std::f32::consts::PI / tips as f32;
+
+        Shape::from_points(
+            (0..tips * 2)
+                .map(|i| {
+                    let radius = if i % 2 == 0 { outer_radius } else { inner_radius };
+                    center + Vector2f::new(radius, 0.0).rotate(rotation + step * i as f32)
+                })
+                .collect(),
+        )
+    }
+
+    pub fn ellipse(center: Vector2f, radii: Vector2f, segments: u32) -> Shape {
+        let segments = segments.max(3);
+        let step = std::f32::consts::TAU / segments as f32;
+
+        Shape::from_points(
+            (0..segments)
+                .map(|i| {
+                    let angle = step * i as f32;
+                    center + Vector2f::new(radii.x() * angle.cos(), radii.y() * angle.sin())
+                })
+                .collect(),
+        )
+    }
+
+    pub fn circle(center: Vector2f, radius: f32, segments: u32) -> Shape {
+        Shape::ellipse(center, Vector2f::new(radius, radius), segments)
+    }
+
+    pub fn rounded_rectangle(
+        corner: Vector2f,
+        opposite: Vector2f,
+        radius: f32,
+        corner_segments: u32,
+    ) -> Shape {
+        let (min, max) = normalized_box(corner, opposite);
+        let radius = radius
+            .min((max.x() - min.x()) / 2.0)
+            .min((max.y() - min.y()) / 2.0)
+            .max(0.0);
+        if radius == 0.0 {
+            return Shape::rectangle(min, max);
         }
+
+        let corner_segments = corner_segments.max(1);
+        let corners = [
+            (Vector2f::new(max.x() - radius, min.y() + radius), -std::f32::consts::FRAC_PI_2),
+            (Vector2f::new(max.x() - radius, max.y() - radius), 0.0),
+            (Vector2f::new(min.x() + radius, max.y() - radius), std::f32::consts::FRAC_PI_2),
+            (Vector2f::new(min.x() + radius, min.y() + radius), std::f32::consts::PI),
+        ];
+        let step = std::f32::consts::FRAC_PI_2 / corner_segments as f32;
+
+        let mut shape = Shape::new();
+        for (center, start) in corners {
+            for i in 0..=corner_segments {
+                shape.add_point(center + Vector2f::new(radius, 0.0).rotate(start + step * i as f32));
+            }
+        }
+
+        shape
+    }
+
+    pub fn arrow(from: Vector2f, to: Vector2f, shaft_width: f32) -> Shape {
+        let length = from.distance_to(to);
+        if length == 0.0 {
+            return Shape::from_points(vec![from]);
+        }
+
+        let direction = (to - from) / length;
+        let normal = Vector2f::new(-direction.y(), direction.x());
+        let head_length = (shaft_width * 3.0).min(length / 2.0);
+        let head_width = shaft_width * 3.0;
+        let neck = to - direction * head_length;
+
+        Shape::from_points(vec![
+            from + normal * (shaft_width / 2.0),
+            neck + normal * (shaft_width / 2.0),
+            neck + normal * (head_width / 2.0),
+            to,
+            neck - normal * (head_width / 2.0),
+            neck - normal * (shaft_width / 2.0),
+            from - normal * (shaft_width / 2.0),
+        ])
     }
 
-    pub fn add_point(&mut self, point: Vector2f) {        
+    /// The closing edge keeps its segment: a new straight edge is inserted before it.
+    pub fn add_point(&mut self, point: Vector2f) {
+        let n = self.points.len();
+        if n > 0 && self.segments.len() >= n {
+            self.segments.insert(n - 1, Segment::Line);
+        }
         self.points.push(point);
     }
 
+    /// Drops the edge leading into the removed point, so the closing edge keeps its segment.
+    pub fn pop_point(&mut self) -> Option<Vector2f> {
+        let n = self.points.len();
+        if n >= 2 && self.segments.len() > n - 2 {
+            self.segments.remove(n - 2);
+        } else if n < 2 {
+            self.segments.clear();
+        }
+        self.points.pop()
+    }
+
     pub fn get_points(&self) -> &Vec<Vector2f> {
         &self.points
     }
 
+    pub fn holes(&self) -> &Vec<Vec<Vector2f>> {
+        &self.holes
+    }
+
+    pub fn add_hole(&mut self, hole: Vec<Vector2f>) {
+        self.holes.push(hole);
+    }
+
+    pub fn add_hole_point(&mut self, point: Vector2f) {
+        match self.holes.last_mut() {
+            Some(hole) => hole.push(point),
+            None => self.holes.push(vec![point]),
+        }
+    }
+
+    pub fn pop_hole(&mut self) -> Option<Vec<Vector2f>> {
+        self.holes.pop()
+    }
+
+    pub fn fill_rule(&self) -> FillRule {
+        self.fill_rule
+    }
+
+    pub fn set_fill_rule(&mut self, fill_rule: FillRule) {
+        self.fill_rule = fill_rule;
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed && self.points.len() > 2
+    }
+
+    /// The stored flag, which `is_closed` only honours once there are three points.
+    pub fn closed_flag(&self) -> bool {
+        self.closed
+    }
+
+    pub fn set_closed(&mut self, closed: bool) {
+        self.closed = closed;
+    }
+
+    pub fn toggle_closed(&mut self) {
+        self.closed = !self.closed;
+    }
+
+    pub fn group(&self) -> Option<u32> {
+        self.group
+    }
+
+    pub fn set_group(&mut self, group: Option<u32>) {
+        self.group = group;
+    }
+
+    pub fn is_clip_mask(&self) -> bool {
+        self.clip_mask
+    }
+
+    pub fn set_clip_mask(&mut self, clip_mask: bool) {
+        self.clip_mask = clip_mask;
+    }
+
+    pub fn rings(&self) -> Vec<Vec<Vector2f>> {
+        let mut rings = vec![self.flatten(FLATTEN_TOLERANCE)];
+        rings.extend(self.holes.iter().filter(|h| h.len() > 2).cloned());
+
+        rings
+    }
+
+    /// How a hole ring changes the covered area under the fill rule, matching `intersect_with_point`:
+    /// -1 if it cuts the outline, 0 if nonzero winding keeps it filled, 1 if it lies outside the outline.
+    /// Holes are assumed not to overlap each other or cross the outline.
+    fn hole_coverage(&self, outline: &[Vector2f], hole: &[Vector2f]) -> f32 {
+        if !ring_contains(outline, hole[0]) {
+            return 1.0;
+        }
+
+        let same_winding = signed_area(outline).signum() == signed_area(hole).signum();
+        match self.fill_rule {
+            FillRule::NonZero if same_winding => 0.0,
+            _ => -1.0,
+        }
+    }
+
+    /// The filled parts of a closed shape as outlines with the holes that cut them: the outline comes
+    /// first, followed by any holes lying outside it, which the fill rule paints as regions of their own.
+    pub fn filled_regions(&self) -> Vec<(Vec<Vector2f>, Vec<Vec<Vector2f>>)> {
+        let outline = self.flatten(FLATTEN_TOLERANCE);
+        let mut cut = Vec::new();
+        let mut outside = Vec::new();
+        for hole in self.holes.iter().filter(|h| h.len() > 2) {
+            let coverage = self.hole_coverage(&outline, hole);
+            if coverage < 0.0 {
+                cut.push(hole.clone());
+            } else if coverage > 0.0 {
+                outside.push((hole.clone(), Vec::new()));
+            }
+        }
+
+        let mut regions = vec![(outline, cut)];
+        regions.extend(outside);
+        regions
+    }
+
+    pub fn area(&self) -> f32 {
+        if !self.is_closed() {
+            return 0.0;
+        }
+
+        let outline = self.flatten(FLATTEN_TOLERANCE);
+        let holes = self
+            .holes
+            .iter()
+            .filter(|h| h.len() > 2)
+            .map(|h| self.hole_coverage(&outline, h) * signed_area(h).abs());
+
+        signed_area(&outline).abs() + holes.sum::<f32>()
+    }
+
+    pub fn centroid(&self) -> Option<Vector2f> {
+        if !self.is_closed() {
+            return None;
+        }
+
+        let rings = self.rings();
+        let mut weighted = Vector2f::zero();
+        let mut total = 0.0;
+        for (i, ring) in rings.iter().enumerate() {
+            let Some(centroid) = ring_centroid(ring) else {
+                continue;
+            };
+            let coverage = if i == 0 { 1.0 } else { self.hole_coverage(&rings[0], ring) };
+            let area = signed_area(ring).abs() * coverage;
+            weighted += centroid * area;
+            total += area;
+        }
+
+        if total.abs() < f32::EPSILON {
+            None
+        } else {
+            Some(weighted / total)
+        }
+    }
+
+    pub fn segment_count(&self) -> usize {
+        if self.is_closed() {
+            self.points.len()
+        } else {
+            self.points.len().saturating_sub(1)
+        }
+    }
+
+    pub fn segment(&self, index: usize) -> Segment {
+        self.segments.get(index).copied().unwrap_or(Segment::Line)
+    }
+
+    pub fn set_segment(&mut self, index: usize, segment: Segment) {
+        if self.segments.len() <= index {
+            self.segments.resize(index + 1, Segment::Line);
+        }
+        self.segments[index] = segment;
+    }
+
+    pub fn set_curve_kind(&mut self, kind: CurveKind) {
+        self.segments = (0..self.segment_count())
+            .map(|i| {
+                let (start, end) = self.segment_endpoints(i);
+                Segment::from_kind(kind, start, end)
+            })
+            .collect();
+    }
+
+    pub fn is_curved(&self) -> bool {
+        self.segments.iter().any(|s| !matches!(s, Segment::Line))
+    }
+
+    pub fn segment_endpoints(&self, index: usize) -> (Vector2f, Vector2f) {
+        let n = self.points.len();
+
+        (self.points[index % n], self.points[(index + 1) % n])
+    }
+
+    pub fn resolved_segment(&self, index: usize) -> Segment {
+        match self.segment(index) {
+            Segment::CatmullRom => {
+                let n = self.points.len();
+                let (start, end) = self.segment_endpoints(index);
+                let (previous, next) = if self.is_closed() {
+                    (self.points[(index + n - 1) % n], self.points[(index + 2) % n])
+                } else {
+                    (
+                        if index == 0 { start } else { self.points[index - 1] },
+                        self.points.get(index + 2).copied().unwrap_or(end),
+                    )
+                };
+                let (first, second) = curve::catmull_rom_to_cubic(previous, start, end, next);
+
+                Segment::Cubic(first, second)
+            }
+            segment => segment,
+        }
+    }
+
+    pub fn flatten(&self, tolerance: f32) -> Vec<Vector2f> {
+        let Some(first) = self.points.first() else {
+            return Vec::new();
+        };
+
+        let mut result = vec![*first];
+        for i in 0..self.segment_count() {
+            self.flatten_segment(i, tolerance, &mut result);
+        }
+
+        if self.is_closed() {
+            result.pop();
+        }
+
+        result
+    }
+
+    fn flatten_segment(&self, index: usize, tolerance: f32, out: &mut Vec<Vector2f>) {
+        let (start, end) = self.segment_endpoints(index);
+        match self.resolved_segment(index) {
+            Segment::Line | Segment::CatmullRom => out.push(end),
+            Segment::Quadratic(control) => {
+                let (first, second) = curve::quadratic_to_cubic(start, control, end);
+                curve::flatten_cubic(start, first, second, end, tolerance, out);
+            }
+            Segment::Cubic(first, second) => {
+                curve::flatten_cubic(start, first, second, end, tolerance, out);
+            }
+        }
+    }
+
+    pub fn nearest_vertex(&self, point: Vector2f) -> Option<(usize, f32)> {
+        self.points
+            .iter()
+            .map(|p| p.distance_to(point))
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    /// For curved segments `t` is measured along the flattened polyline rather than
+    /// the curve parameter.
+    pub fn nearest_edge(&self, point: Vector2f) -> Option<EdgeHit> {
+        let mut best: Option<EdgeHit> = None;
+
+        for i in 0..self.segment_count() {
+            let mut polyline = vec![self.segment_endpoints(i).0];
+            self.flatten_segment(i, FLATTEN_TOLERANCE, &mut polyline);
+
+            let pieces = (polyline.len() - 1) as f32;
+            for (k, piece) in polyline.windows(2).enumerate() {
+                let (local_t, closest) = closest_on_segment(point, piece[0], piece[1]);
+                let distance = point.distance_to(closest);
+                if best.is_none_or(|b| distance < b.distance) {
+                    best = Some(EdgeHit {
+                        segment: i,
+                        t: (k as f32 + local_t) / pieces,
+                        point: closest,
+                        distance,
+                    });
+                }
+            }
+        }
+
+        best
+    }
+
+    pub fn distance_to_point(&self, point: Vector2f) -> f32 {
+        if self.is_closed() && self.intersect_with_point(point) {
+            return 0.0;
+        }
+
+        let outline = self
+            .nearest_edge(point)
+            .map(|hit| hit.distance)
+            .or_else(|| self.points.first().map(|p| p.distance_to(point)))
+            .unwrap_or(f32::INFINITY);
+        let holes = self
+            .holes
+            .iter()
+            .filter(|h| !h.is_empty())
+            .flat_map(|h| {
+                h.iter()
+                    .zip(h.iter().cycle().skip(1))
+                    .map(|(a, b)| distance_to_segment(point, *a, *b))
+            })
+            .fold(f32::INFINITY, f32::min);
+
+        outline.min(holes)
+    }
+
+    pub fn handles(&self) -> Vec<(Handle, Vector2f)> {
+        let mut handles: Vec<(Handle, Vector2f)> = self
+            .points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (Handle::Vertex(i), *p))
+            .collect();
+
+        for (i, segment) in self.segments.iter().enumerate().take(self.segment_count()) {
+            for (j, control) in segment.control_points().into_iter().enumerate() {
+                handles.push((Handle::Control(i, j), control));
+            }
+        }
+
+        for (i, hole) in self.holes.iter().enumerate() {
+            for (j, point) in hole.iter().enumerate() {
+                handles.push((Handle::HoleVertex(i, j), *point));
+            }
+        }
+
+        handles
+    }
+
+    pub fn move_handle(&mut self, handle: Handle, position: Vector2f) {
+        match handle {
+            Handle::Vertex(i) => {
+                if let Some(point) = self.points.get_mut(i) {
+                    *point = position;
+                }
+            }
+            Handle::Control(i, j) => {
+                if let Some(control) = self
+                    .segments
+                    .get_mut(i)
+                    .and_then(|s| s.control_points_mut().into_iter().nth(j))
+                {
+                    *control = position;
+                }
+            }
+            Handle::HoleVertex(i, j) => {
+                if let Some(point) = self.holes.get_mut(i).and_then(|h| h.get_mut(j)) {
+                    *point = position;
+                }
+            }
+        }
+    }
+
+    fn points_mut(&mut self) -> impl Iterator<Item = &mut Vector2f> {
+        self.points
+            .iter_mut()
+            .chain(
+                self.segments
+                    .iter_mut()
+                    .flat_map(|s| s.control_points_mut()),
+            )
+            .chain(self.holes.iter_mut().flatten())
+    }
+
+    /// Every vertex, curve control and hole point, in a stable order for comparing two versions of a shape.
+    pub fn control_points(&self) -> Vec<Vector2f> {
+        self.points
+            .iter()
+            .copied()
+            .chain(self.segments.iter().flat_map(|s| s.control_points()))
+            .chain(self.holes.iter().flatten().copied())
+            .collect()
+    }
+
+    pub fn bounds(&self) -> Option<(Vector2f, Vector2f)> {
+        let points = self.flatten(FLATTEN_TOLERANCE);
+        let first = *points.first()?;
+
+        Some(points.iter().fold((first, first), |(min, max), pt| {
+            (
+                Vector2f::new(min.x().min(pt.x()), min.y().min(pt.y())),
+                Vector2f::new(max.x().max(pt.x()), max.y().max(pt.y())),
+            )
+        }))
+    }
+
+    pub fn map_points(&mut self, f: impl Fn(Vector2f) -> Vector2f) {
+        for point in self.points_mut() {
+            *point = f(*point);
+        }
+    }
+
     pub fn shift(&mut self, shift: Vector2f) {
-        for point in self.points.iter_mut() {
+        for point in self.points_mut() {
             point.set_x(point.x() + shift.x());
             point.set_y(point.y() + shift.y());
         }
     }
 
     pub fn rotate_rel_to_point(&mut self, angle: f32, point: Vector2f) {
-        for pt in self.points.iter_mut() {
+        for pt in self.points_mut() {
             let x = point.x() + (pt.x() - point.x()) * angle.cos() - (pt.y() - point.y()) * angle.sin();
             let y = point.y() + (pt.x() - point.x()) * angle.sin() + (pt.y() - point.y()) * angle.cos();
 
@@ -40,7 +605,7 @@ impl Shape {
     }
 
     pub fn scale_rel_to_point(&mut self, scale: Vector2f, point: Vector2f) {
-        for pt in self.points.iter_mut() {
+        for pt in self.points_mut() {
             let x = point.x() + (pt.x() - point.x()) * scale.x();
             let y = point.y() + (pt.y() - point.y()) * scale.y();
 
@@ -50,21 +615,178 @@ impl Shape {
     }
 
     pub fn intersect_with_point(&self, point: Vector2f) -> bool {
+        if !self.is_closed() {
+            let outline = self.flatten(FLATTEN_TOLERANCE);
+            return match outline.len() {
+                0 => false,
+                1 => outline[0].distance_to(point) <= OPEN_HIT_TOLERANCE,
+                _ => outline
+                    .windows(2)
+                    .any(|w| distance_to_segment(point, w[0], w[1]) <= OPEN_HIT_TOLERANCE),
+            };
+        }
+
         let mut intersections = 0;
-        let Some(mut prev_point) = self.points.last() else {
-            return false;
-        };
+        let mut winding = 0;
+
+        for ring in self.rings() {
+            let Some(mut prev_point) = ring.last() else {
+                continue;
+            };
 
-        for pt in self.points.iter() {
-            if (pt.y() > point.y()) != (prev_point.y() > point.y()) {
-                let x = (point.y() - pt.y()) * (prev_point.x() - pt.x()) / (prev_point.y() - pt.y()) + pt.x();
-                if x > point.x() {
-                    intersections += 1;
+            for pt in ring.iter() {
+                if (pt.y() > point.y()) != (prev_point.y() > point.y()) {
+                    let x = (point.y() - pt.y()) * (prev_point.x() - pt.x()) / (prev_point.y() - pt.y()) + pt.x();
+                    if x > point.x() {
+                        intersections += 1;
+                        winding += if pt.y() > prev_point.y() { 1 } else { -1 };
+                    }
                 }
+                prev_point = pt;
+            }
+        }
+
+        match self.fill_rule {
+            FillRule::EvenOdd => intersections % 2 == 1,
+            FillRule::NonZero => winding != 0,
+        }
+    }
+
+    pub fn self_intersections(&self) -> Vec<Crossing> {
+        simplify::self_intersections(&self.flatten(FLATTEN_TOLERANCE), self.is_closed())
+    }
+
+    /// Splits a closed outline at its self-intersections; each hole goes to the piece containing it.
+    /// Returns `None` when the outline is too tangled to split.
+    pub fn split_self_intersections(&self) -> Option<Vec<Shape>> {
+        let mut pieces: Vec<Shape> = simplify::split_simple(&self.flatten(FLATTEN_TOLERANCE))?
+            .into_iter()
+            .map(|ring| {
+                let mut piece = Shape::from_points(ring);
+                piece.fill_rule = self.fill_rule;
+                piece.group = self.group;
+                piece.clip_mask = self.clip_mask;
+                piece
+            })
+            .collect();
+
+        for hole in self.holes.iter().filter(|h| !h.is_empty()) {
+            if let Some(piece) = pieces.iter_mut().find(|p| p.intersect_with_point(hole[0])) {
+                piece.holes.push(hole.clone());
             }
-            prev_point = pt;
         }
 
-        intersections % 2 == 1
+        Some(pieces)
+    }
+
+    /// Flattens curves and splits every edge so no segment is longer than `max_length`.
+    pub fn subdivided(&self, max_length: f32) -> Shape {
+        let mut result = Shape::from_points(subdivide_ring(
+            &self.flatten(FLATTEN_TOLERANCE),
+            self.closed,
+            max_length,
+        ));
+        result.holes = self
+            .holes
+            .iter()
+            .map(|hole| subdivide_ring(hole, true, max_length))
+            .collect();
+        result.fill_rule = self.fill_rule;
+        result.closed = self.closed;
+        result.group = self.group;
+        result.clip_mask = self.clip_mask;
+        result
+    }
+}
+
+fn subdivide_ring(ring: &[Vector2f], closed: bool, max_length: f32) -> Vec<Vector2f> {
+    if max_length <= 0.0 || ring.len() < 2 {
+        return ring.to_vec();
+    }
+
+    let edges = if closed { ring.len() } else { ring.len() - 1 };
+    let mut result = Vec::with_capacity(ring.len());
+    for i in 0..edges {
+        let (start, end) = (ring[i], ring[(i + 1) % ring.len()]);
+        let steps = (start.distance_to(end) / max_length).ceil().max(1.0) as usize;
+        result.extend((0..steps).map(|step| start + (end - start) * (step as f32 / steps as f32)));
     }
-}
\ No newline at end of file
+
+    if !closed {
+        result.push(ring[ring.len() - 1]);
+    }
+
+    result
+}
+
+fn normalized_box(corner: Vector2f, opposite: Vector2f) -> (Vector2f, Vector2f) {
+    (
+        Vector2f::new(corner.x().min(opposite.x()), corner.y().min(opposite.y())),
+        Vector2f::new(corner.x().max(opposite.x()), corner.y().max(opposite.y())),
+    )
+}
+
+pub fn signed_area(ring: &[Vector2f]) -> f32 {
+    let Some(mut prev) = ring.last() else {
+        return 0.0;
+    };
+
+    let mut area = 0.0;
+    for point in ring {
+        area += prev.cross(*point);
+        prev = point;
+    }
+
+    area / 2.0
+}
+
+pub fn ring_contains(ring: &[Vector2f], point: Vector2f) -> bool {
+    let Some(mut prev) = ring.last() else {
+        return false;
+    };
+
+    let mut inside = false;
+    for current in ring {
+        if (current.y() > point.y()) != (prev.y() > point.y()) {
+            let x = (point.y() - current.y()) * (prev.x() - current.x()) / (prev.y() - current.y())
+                + current.x();
+            if x > point.x() {
+                inside = !inside;
+            }
+        }
+        prev = current;
+    }
+
+    inside
+}
+
+fn ring_centroid(ring: &[Vector2f]) -> Option<Vector2f> {
+    let area = signed_area(ring);
+    if area.abs() < f32::EPSILON {
+        return None;
+    }
+
+    let mut prev = ring.last()?;
+    let mut sum = Vector2f::zero();
+    for point in ring {
+        sum += (*prev + *point) * prev.cross(*point);
+        prev = point;
+    }
+
+    Some(sum / (6.0 * area))
+}
+
+fn distance_to_segment(point: Vector2f, start: Vector2f, end: Vector2f) -> f32 {
+    point.distance_to(closest_on_segment(point, start, end).1)
+}
+
+fn closest_on_segment(point: Vector2f, start: Vector2f, end: Vector2f) -> (f32, Vector2f) {
+    let segment = end - start;
+    let length_squared = segment.dot(segment);
+    if length_squared == 0.0 {
+        return (0.0, start);
+    }
+
+    let t = ((point - start).dot(segment) / length_squared).clamp(0.0, 1.0);
+    (t, start + segment * t)
+}
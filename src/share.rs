@@ -0,0 +1,228 @@
+use crate::{
+    curve::Segment,
+    shape::{FillRule, Shape},
+    vec::Vector2f,
+};
+
+pub const FRAGMENT_PREFIX: &str = "#scene=";
+pub const MAX_LINK_LENGTH: usize = 2000;
+
+const VERSION: u8 = 2;
+const QUANTUM: f32 = 0.1;
+const MAX_DECODED_SIZE: usize = 1 << 20;
+/// Keeps quantized coordinates and the deltas between them far from `i64` overflow.
+const MAX_COORDINATE: f32 = 1.0e9;
+
+const FLAG_CURVED: u8 = 1;
+const FLAG_HOLES: u8 = 2;
+const FLAG_NONZERO: u8 = 4;
+const FLAG_OPEN: u8 = 8;
+const FLAG_GROUPED: u8 = 16;
+const FLAG_CLIP_MASK: u8 = 32;
+
+const SEGMENT_LINE: u8 = 0;
+const SEGMENT_QUADRATIC: u8 = 1;
+const SEGMENT_CUBIC: u8 = 2;
+const SEGMENT_CATMULL_ROM: u8 = 3;
+
+/// Returns `None` if a coordinate is not finite or beyond `MAX_COORDINATE`.
+pub fn encode(shapes: &[Shape]) -> Option<String> {
+    let mut bytes = vec![VERSION];
+    write_varint(&mut bytes, shapes.len() as u64);
+
+    let mut prev = (0i64, 0i64);
+    for shape in shapes {
+        let points = shape.get_points();
+        write_varint(&mut bytes, points.len() as u64);
+
+        for point in points {
+            write_point(&mut bytes, &mut prev, *point)?;
+        }
+
+        let mut flags = 0;
+        if shape.is_curved() {
+            flags |= FLAG_CURVED;
+        }
+        if !shape.holes().is_empty() {
+            flags |= FLAG_HOLES;
+        }
+        if shape.fill_rule() == FillRule::NonZero {
+            flags |= FLAG_NONZERO;
+        }
+        if !shape.closed_flag() {
+            flags |= FLAG_OPEN;
+        }
+        if shape.group().is_some() {
+            flags |= FLAG_GROUPED;
+        }
+        if shape.is_clip_mask() {
+            flags |= FLAG_CLIP_MASK;
+        }
+        bytes.push(flags);
+
+        if flags & FLAG_CURVED != 0 {
+            for i in 0..shape.segment_count() {
+                match shape.segment(i) {
+                    Segment::Line => bytes.push(SEGMENT_LINE),
+                    Segment::Quadratic(control) => {
+                        bytes.push(SEGMENT_QUADRATIC);
+                        write_point(&mut bytes, &mut prev, control)?;
+                    }
+                    Segment::Cubic(first, second) => {
+                        bytes.push(SEGMENT_CUBIC);
+                        write_point(&mut bytes, &mut prev, first)?;
+                        write_point(&mut bytes, &mut prev, second)?;
+                    }
+                    Segment::CatmullRom => bytes.push(SEGMENT_CATMULL_ROM),
+                }
+            }
+        }
+
+        if flags & FLAG_HOLES != 0 {
+            write_varint(&mut bytes, shape.holes().len() as u64);
+            for hole in shape.holes() {
+                write_varint(&mut bytes, hole.len() as u64);
+                for point in hole {
+                    write_point(&mut bytes, &mut prev, *point)?;
+                }
+            }
+        }
+
+        if let Some(group) = shape.group() {
+            write_varint(&mut bytes, group as u64);
+        }
+    }
+
+    let compressed = miniz_oxide::deflate::compress_to_vec(&bytes, 9);
+    Some(base64::encode_config(compressed, base64::URL_SAFE_NO_PAD))
+}
+
+pub fn decode(data: &str) -> Option<Vec<Shape>> {
+    let compressed = base64::decode_config(data, base64::URL_SAFE_NO_PAD).ok()?;
+    let bytes = miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, MAX_DECODED_SIZE).ok()?;
+    let mut bytes = bytes.into_iter();
+
+    let version = bytes.next()?;
+    if version == 0 || version > VERSION {
+        return None;
+    }
+
+    let shape_count = read_varint(&mut bytes)?;
+    let mut shapes = Vec::new();
+    let mut prev = (0i64, 0i64);
+    for _ in 0..shape_count {
+        let point_count = read_varint(&mut bytes)?;
+        let mut shape = Shape::new();
+        for _ in 0..point_count {
+            shape.add_point(read_point(&mut bytes, &mut prev)?);
+        }
+
+        let flags = if version >= 2 { bytes.next()? } else { 0 };
+        shape.set_closed(flags & FLAG_OPEN == 0);
+        if flags & FLAG_CURVED != 0 {
+            for i in 0..shape.segment_count() {
+                let segment = match bytes.next()? {
+                    SEGMENT_LINE => Segment::Line,
+                    SEGMENT_QUADRATIC => Segment::Quadratic(read_point(&mut bytes, &mut prev)?),
+                    SEGMENT_CUBIC => Segment::Cubic(
+                        read_point(&mut bytes, &mut prev)?,
+                        read_point(&mut bytes, &mut prev)?,
+                    ),
+                    SEGMENT_CATMULL_ROM => Segment::CatmullRom,
+                    _ => return None,
+                };
+                shape.set_segment(i, segment);
+            }
+        }
+
+        if flags & FLAG_HOLES != 0 {
+            let hole_count = read_varint(&mut bytes)?;
+            for _ in 0..hole_count {
+                let point_count = read_varint(&mut bytes)?;
+                let mut hole = Vec::new();
+                for _ in 0..point_count {
+                    hole.push(read_point(&mut bytes, &mut prev)?);
+                }
+                shape.add_hole(hole);
+            }
+        }
+
+        if flags & FLAG_GROUPED != 0 {
+            shape.set_group(Some(u32::try_from(read_varint(&mut bytes)?).ok()?));
+        }
+
+        if flags & FLAG_NONZERO != 0 {
+            shape.set_fill_rule(FillRule::NonZero);
+        }
+        shape.set_clip_mask(flags & FLAG_CLIP_MASK != 0);
+
+        shapes.push(shape);
+    }
+
+    if bytes.next().is_some() {
+        return None;
+    }
+
+    Some(shapes)
+}
+
+pub fn decode_fragment(fragment: &str) -> Option<Vec<Shape>> {
+    decode(fragment.strip_prefix(FRAGMENT_PREFIX)?)
+}
+
+fn write_point(bytes: &mut Vec<u8>, prev: &mut (i64, i64), point: Vector2f) -> Option<()> {
+    let quantized = (quantize(point.x())?, quantize(point.y())?);
+    write_varint(bytes, zigzag(quantized.0.checked_sub(prev.0)?));
+    write_varint(bytes, zigzag(quantized.1.checked_sub(prev.1)?));
+    *prev = quantized;
+
+    Some(())
+}
+
+fn read_point(bytes: &mut impl Iterator<Item = u8>, prev: &mut (i64, i64)) -> Option<Vector2f> {
+    prev.0 = prev.0.checked_add(unzigzag(read_varint(bytes)?))?;
+    prev.1 = prev.1.checked_add(unzigzag(read_varint(bytes)?))?;
+
+    Some(Vector2f::new(dequantize(prev.0), dequantize(prev.1)))
+}
+
+fn quantize(value: f32) -> Option<i64> {
+    if !value.is_finite() || value.abs() > MAX_COORDINATE {
+        return None;
+    }
+
+    Some((value / QUANTUM).round() as i64)
+}
+
+fn dequantize(value: i64) -> f32 {
+    value as f32 * QUANTUM
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+fn read_varint(bytes: &mut impl Iterator<Item = u8>) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = bytes.next()?;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+
+    None
+}
@@ -0,0 +1,116 @@
+use crate::{shape::signed_area, vec::Vector2f};
+
+const MAX_SPLIT_DEPTH: u32 = 64;
+const ENDPOINT_EPSILON: f32 = 1e-5;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Crossing {
+    pub edges: (usize, usize),
+    pub point: Vector2f,
+}
+
+/// Sweeps the edges of `ring` along x and reports every pair of non-adjacent edges that cross.
+pub fn self_intersections(ring: &[Vector2f], closed: bool) -> Vec<Crossing> {
+    let n = ring.len();
+    let edge_count = match (closed, n) {
+        (_, 0..=1) => 0,
+        (true, _) => n,
+        (false, _) => n - 1,
+    };
+    let edge = |i: usize| (ring[i], ring[(i + 1) % n]);
+    let min_x = |i: usize| {
+        let (a, b) = edge(i);
+        a.x().min(b.x())
+    };
+    let max_x = |i: usize| {
+        let (a, b) = edge(i);
+        a.x().max(b.x())
+    };
+
+    let mut order: Vec<usize> = (0..edge_count).collect();
+    order.sort_by(|a, b| min_x(*a).total_cmp(&min_x(*b)));
+
+    let mut crossings = Vec::new();
+    let mut active: Vec<usize> = Vec::new();
+    for current in order {
+        active.retain(|&other| max_x(other) >= min_x(current));
+
+        for &other in &active {
+            let (i, j) = (current.min(other), current.max(other));
+            if j - i == 1 || (closed && i == 0 && j == edge_count - 1) {
+                continue;
+            }
+
+            let (a, b) = edge(i);
+            let (c, d) = edge(j);
+            if let Some(point) = segment_intersection(a, b, c, d) {
+                crossings.push(Crossing {
+                    edges: (i, j),
+                    point,
+                });
+            }
+        }
+
+        active.push(current);
+    }
+
+    crossings.sort_by_key(|c| c.edges);
+    crossings
+}
+
+/// Splits a closed ring at its crossings until every piece is a simple polygon.
+/// Returns `None` if the ring needs more than `MAX_SPLIT_DEPTH` nested splits.
+pub fn split_simple(ring: &[Vector2f]) -> Option<Vec<Vec<Vector2f>>> {
+    let mut pieces = Vec::new();
+    split_rec(ring.to_vec(), 0, &mut pieces)?;
+    Some(pieces)
+}
+
+fn split_rec(ring: Vec<Vector2f>, depth: u32, out: &mut Vec<Vec<Vector2f>>) -> Option<()> {
+    if ring.len() < 3 {
+        return Some(());
+    }
+
+    let Some(Crossing {
+        edges: (i, j),
+        point,
+    }) = self_intersections(&ring, true).into_iter().next()
+    else {
+        if signed_area(&ring).abs() > f32::EPSILON {
+            out.push(ring);
+        }
+        return Some(());
+    };
+    if depth >= MAX_SPLIT_DEPTH {
+        return None;
+    }
+
+    let mut first = vec![point];
+    first.extend_from_slice(&ring[i + 1..=j]);
+
+    let mut second = ring[j + 1..].to_vec();
+    second.extend_from_slice(&ring[..=i]);
+    second.push(point);
+
+    split_rec(first, depth + 1, out)?;
+    split_rec(second, depth + 1, out)
+}
+
+fn segment_intersection(a: Vector2f, b: Vector2f, c: Vector2f, d: Vector2f) -> Option<Vector2f> {
+    let ab = b - a;
+    let cd = d - c;
+    let denominator = ab.cross(cd);
+    if denominator.abs() <= f32::EPSILON {
+        return None;
+    }
+
+    // Exclusive bounds: edges that merely touch at a vertex do not cross.
+    let t = (c - a).cross(cd) / denominator;
+    let u = (c - a).cross(ab) / denominator;
+    let inside = |v: f32| v > ENDPOINT_EPSILON && v < 1.0 - ENDPOINT_EPSILON;
+    if inside(t) && inside(u) {
+        Some(a + ab * t)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,471 @@
+use crate::{
+    curve::Segment,
+    shape::{FillRule, Shape, FLATTEN_TOLERANCE},
+    vec::Vector2f,
+};
+
+pub fn to_svg(shapes: &[Shape], width: f32, height: f32) -> String {
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+        width, height
+    );
+
+    let (masks, shapes): (Vec<&Shape>, Vec<&Shape>) = shapes
+        .iter()
+        .filter(|s| !s.get_points().is_empty())
+        .partition(|s| s.is_clip_mask() && s.is_closed());
+
+    if !masks.is_empty() {
+        svg.push_str("  <defs>\n");
+        for (i, mask) in masks.iter().enumerate() {
+            svg.push_str(&format!(
+                "    <clipPath id=\"clip-mask-{}\"><path d=\"{}\"/></clipPath>\n",
+                i,
+                path_data(mask)
+            ));
+        }
+        svg.push_str("  </defs>\n");
+    }
+    for i in 0..masks.len() {
+        svg.push_str(&format!("  <g clip-path=\"url(#clip-mask-{})\">\n", i));
+    }
+
+    for shape in shapes {
+        let fill = if shape.is_closed() {
+            let fill_rule = match shape.fill_rule() {
+                FillRule::EvenOdd => "evenodd",
+                FillRule::NonZero => "nonzero",
+            };
+            format!(
+                "fill=\"cornflowerblue\" fill-opacity=\"0.3\" fill-rule=\"{}\"",
+                fill_rule
+            )
+        } else {
+            "fill=\"none\"".to_string()
+        };
+
+        if shape.is_curved() || !shape.holes().is_empty() {
+            svg.push_str(&format!(
+                "  <path d=\"{}\" {} stroke=\"black\"/>\n",
+                path_data(shape),
+                fill
+            ));
+            continue;
+        }
+
+        let points = shape
+            .get_points()
+            .iter()
+            .map(|p| format!("{},{}", p.x(), p.y()))
+            .collect::<Vec<String>>()
+            .join(" ");
+        let element = if shape.is_closed() {
+            "polygon"
+        } else {
+            "polyline"
+        };
+        svg.push_str(&format!(
+            "  <{} points=\"{}\" {} stroke=\"black\"/>\n",
+            element, points, fill
+        ));
+    }
+
+    for _ in 0..masks.len() {
+        svg.push_str("  </g>\n");
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn path_data(shape: &Shape) -> String {
+    let points = shape.get_points();
+    let mut data = format!("M {} {}", points[0].x(), points[0].y());
+
+    for i in 0..shape.segment_count() {
+        let (_, end) = shape.segment_endpoints(i);
+        let command = match shape.resolved_segment(i) {
+            Segment::Line | Segment::CatmullRom => format!(" L {} {}", end.x(), end.y()),
+            Segment::Quadratic(control) => {
+                format!(" Q {} {} {} {}", control.x(), control.y(), end.x(), end.y())
+            }
+            Segment::Cubic(first, second) => format!(
+                " C {} {} {} {} {} {}",
+                first.x(),
+                first.y(),
+                second.x(),
+                second.y(),
+                end.x(),
+                end.y()
+            ),
+        };
+        data.push_str(&command);
+    }
+
+    if shape.is_closed() {
+        data.push_str(" Z");
+    }
+
+    for hole in shape.holes().iter().filter(|h| !h.is_empty()) {
+        data.push_str(&format!(" M {} {}", hole[0].x(), hole[0].y()));
+        for point in hole.iter().skip(1) {
+            data.push_str(&format!(" L {} {}", point.x(), point.y()));
+        }
+        data.push_str(" Z");
+    }
+
+    data
+}
+
+pub fn from_svg(text: &str) -> Option<Vec<Shape>> {
+    if !text.contains("<svg") {
+        return None;
+    }
+
+    let mut shapes = Vec::new();
+    let mut in_clip_path = false;
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        rest = &rest[start + 1..];
+        let end = rest.find('>')?;
+        let tag = &rest[..end];
+        rest = &rest[end + 1..];
+
+        let name = tag.split_whitespace().next().unwrap_or_default();
+        match name {
+            "clipPath" => in_clip_path = true,
+            "/clipPath" => in_clip_path = false,
+            _ => {}
+        }
+
+        let parsed = match name {
+            "polygon" | "polyline" => attribute(tag, "points").map(|points| {
+                let mut shape = parse_points(points);
+                shape.set_closed(name == "polygon");
+                vec![shape]
+            }),
+            "path" => match attribute(tag, "d") {
+                Some(d) => {
+                    let fill_rule = match attribute(tag, "fill-rule") {
+                        Some("evenodd") => FillRule::EvenOdd,
+                        _ => FillRule::NonZero,
+                    };
+                    Some(group_holes(parse_path(d)?, fill_rule))
+                }
+                None => None,
+            },
+            _ => None,
+        };
+
+        if let Some(parsed) = parsed {
+            shapes.extend(
+                parsed
+                    .into_iter()
+                    .filter(|s| !s.get_points().is_empty())
+                    .map(|mut s| {
+                        s.set_clip_mask(in_clip_path);
+                        s
+                    }),
+            );
+        }
+    }
+
+    if shapes.is_empty() {
+        None
+    } else {
+        Some(shapes)
+    }
+}
+
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    while let Some(index) = rest.find(name) {
+        let preceded_by_space = rest[..index]
+            .chars()
+            .last()
+            .is_some_and(|c| c.is_whitespace());
+        rest = &rest[index + name.len()..];
+
+        let value = rest.trim_start();
+        if !preceded_by_space || !value.starts_with('=') {
+            continue;
+        }
+
+        let value = value[1..].trim_start();
+        let quote = value.chars().next()?;
+        if quote != '"' && quote != '\'' {
+            return None;
+        }
+        let value = &value[1..];
+        return value.find(quote).map(|end| &value[..end]);
+    }
+
+    None
+}
+
+fn numbers(text: &str) -> Vec<f32> {
+    text.split(|c: char| c.is_whitespace() || c == ',')
+        .filter_map(|n| n.parse::<f32>().ok())
+        .collect()
+}
+
+fn parse_points(text: &str) -> Shape {
+    let points = numbers(text)
+        .chunks_exact(2)
+        .map(|c| Vector2f::new(c[0], c[1]))
+        .collect();
+
+    Shape::from_points(points)
+}
+
+/// Returns `None` for commands it cannot represent or argument lists of the wrong length,
+/// rather than silently dropping part of the outline.
+fn parse_path(text: &str) -> Option<Vec<Shape>> {
+    let mut shapes = Vec::new();
+    let mut ring = PathRing::default();
+    let mut current = Vector2f::zero();
+    let mut start = Vector2f::zero();
+    // The last curve's final control point, for the reflected first control of S and T.
+    let mut last_cubic: Option<Vector2f> = None;
+    let mut last_quadratic: Option<Vector2f> = None;
+
+    let mut commands = Vec::new();
+    let mut command_start = None;
+    for (index, c) in text.char_indices() {
+        if c.is_ascii_alphabetic() && c != 'e' && c != 'E' {
+            if let Some(begin) = command_start {
+                commands.push(&text[begin..index]);
+            }
+            command_start = Some(index);
+        } else if command_start.is_none() && !c.is_whitespace() {
+            return None;
+        }
+    }
+    if let Some(begin) = command_start {
+        commands.push(&text[begin..]);
+    }
+
+    for (position, command) in commands.into_iter().enumerate() {
+        let letter = command.chars().next().unwrap_or_default();
+        let args = numbers(&command[1..]);
+        let relative = letter.is_ascii_lowercase();
+        let origin = |current: Vector2f| if relative { current } else { Vector2f::zero() };
+        let arity = match letter.to_ascii_uppercase() {
+            'M' | 'L' | 'T' => 2,
+            'H' | 'V' => 1,
+            'Q' | 'S' => 4,
+            'C' => 6,
+            'A' => 7,
+            'Z' => 0,
+            _ => return None,
+        };
+        let complete = match arity {
+            0 => args.is_empty(),
+            _ => !args.is_empty() && args.len().is_multiple_of(arity),
+        };
+        if !complete {
+            return None;
+        }
+
+        let is_move = letter.eq_ignore_ascii_case(&'M');
+        // Only Z empties the ring mid-path; drawing on after it continues from the closed subpath's start.
+        if position > 0 && ring.points.is_empty() && !is_move && arity > 0 {
+            ring.push(current, Segment::Line);
+        }
+        let (mut next_cubic, mut next_quadratic) = (None, None);
+        match letter.to_ascii_uppercase() {
+            'M' | 'L' => {
+                for (index, pair) in args.chunks_exact(2).enumerate() {
+                    current = origin(current) + Vector2f::new(pair[0], pair[1]);
+                    if is_move && index == 0 {
+                        shapes.extend(std::mem::take(&mut ring).finish(false));
+                        start = current;
+                    }
+                    ring.push(current, Segment::Line);
+                }
+            }
+            'H' => {
+                for x in args {
+                    current = Vector2f::new(origin(current).x() + x, current.y());
+                    ring.push(current, Segment::Line);
+                }
+            }
+            'V' => {
+                for y in args {
+                    current = Vector2f::new(current.x(), origin(current).y() + y);
+                    ring.push(current, Segment::Line);
+                }
+            }
+            'Q' | 'T' => {
+                for values in args.chunks_exact(arity) {
+                    let base = origin(current);
+                    let control = match values {
+                        [x, y, ..] if arity == 4 => base + Vector2f::new(*x, *y),
+                        _ => reflect(next_quadratic.or(last_quadratic), current),
+                    };
+                    current = base + Vector2f::new(values[arity - 2], values[arity - 1]);
+                    ring.push(current, Segment::Quadratic(control));
+                    next_quadratic = Some(control);
+                }
+            }
+            'C' | 'S' => {
+                for values in args.chunks_exact(arity) {
+                    let base = origin(current);
+                    let first = match values {
+                        [x, y, _, _, _, _] => base + Vector2f::new(*x, *y),
+                        _ => reflect(next_cubic.or(last_cubic), current),
+                    };
+                    let second = base + Vector2f::new(values[arity - 4], values[arity - 3]);
+                    current = base + Vector2f::new(values[arity - 2], values[arity - 1]);
+                    ring.push(current, Segment::Cubic(first, second));
+                    next_cubic = Some(second);
+                }
+            }
+            'A' => {
+                for values in args.chunks_exact(7) {
+                    let end = origin(current) + Vector2f::new(values[5], values[6]);
+                    let radii = Vector2f::new(values[0].abs(), values[1].abs());
+                    let (large_arc, sweep) = (values[3] != 0.0, values[4] != 0.0);
+                    let arcs = arc_to_cubics(current, radii, values[2].to_radians(), large_arc, sweep, end);
+                    if arcs.is_empty() {
+                        ring.push(end, Segment::Line);
+                    }
+                    for (first, second, point) in arcs {
+                        ring.push(point, Segment::Cubic(first, second));
+                    }
+                    current = end;
+                }
+            }
+            _ => {
+                shapes.extend(std::mem::take(&mut ring).finish(true));
+                current = start;
+            }
+        }
+        (last_cubic, last_quadratic) = (next_cubic, next_quadratic);
+    }
+
+    shapes.extend(ring.finish(false));
+
+    Some(shapes)
+}
+
+/// Mirrors the previous curve's control point through `current`; without one the control sits on `current`.
+fn reflect(control: Option<Vector2f>, current: Vector2f) -> Vector2f {
+    control.map_or(current, |control| current * 2.0 - control)
+}
+
+/// Converts an SVG elliptical arc to cubic Béziers of at most a quarter turn each, following the
+/// endpoint-to-center conversion in the SVG spec. Degenerate arcs come back empty and are drawn as lines.
+fn arc_to_cubics(
+    from: Vector2f,
+    radii: Vector2f,
+    rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+    to: Vector2f,
+) -> Vec<(Vector2f, Vector2f, Vector2f)> {
+    let (mut rx, mut ry) = (radii.x(), radii.y());
+    if from.distance_to(to) < f32::EPSILON || rx < f32::EPSILON || ry < f32::EPSILON {
+        return Vec::new();
+    }
+
+    let (sin, cos) = rotation.sin_cos();
+    let half = (from - to) / 2.0;
+    let x1 = cos * half.x() + sin * half.y();
+    let y1 = -sin * half.x() + cos * half.y();
+
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+    let coefficient = sign * (numerator / denominator).max(0.0).sqrt();
+    let (cx, cy) = (coefficient * rx * y1 / ry, -coefficient * ry * x1 / rx);
+    let center = Vector2f::new(cos * cx - sin * cy, sin * cx + cos * cy) + (from + to) / 2.0;
+
+    let angle = |x: f32, y: f32| y.atan2(x);
+    let start_angle = angle((x1 - cx) / rx, (y1 - cy) / ry);
+    let mut sweep_angle = angle((-x1 - cx) / rx, (-y1 - cy) / ry) - start_angle;
+    if sweep && sweep_angle < 0.0 {
+        sweep_angle += 2.0 * std::f32::consts::PI;
+    } else if !sweep && sweep_angle > 0.0 {
+        sweep_angle -= 2.0 * std::f32::consts::PI;
+    }
+
+    let map = |x: f32, y: f32| {
+        center + Vector2f::new(cos * rx * x - sin * ry * y, sin * rx * x + cos * ry * y)
+    };
+    let count = (sweep_angle.abs() / std::f32::consts::FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = sweep_angle / count as f32;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+
+    (0..count)
+        .map(|i| {
+            let (a, b) = (start_angle + step * i as f32, start_angle + step * (i + 1) as f32);
+            let first = map(a.cos() - k * a.sin(), a.sin() + k * a.cos());
+            let second = map(b.cos() + k * b.sin(), b.sin() - k * b.cos());
+            let end = if i + 1 == count { to } else { map(b.cos(), b.sin()) };
+            (first, second, end)
+        })
+        .collect()
+}
+
+fn group_holes(rings: Vec<Shape>, fill_rule: FillRule) -> Vec<Shape> {
+    let mut shapes: Vec<Shape> = Vec::new();
+
+    for mut ring in rings {
+        let first = ring.get_points()[0];
+        match shapes.last_mut() {
+            Some(outer)
+                if outer.get_points().len() > 2
+                    && ring.get_points().len() > 2
+                    && Shape::from_points(outer.flatten(FLATTEN_TOLERANCE))
+                        .intersect_with_point(first) =>
+            {
+                outer.add_hole(ring.flatten(FLATTEN_TOLERANCE));
+            }
+            _ => {
+                ring.set_fill_rule(fill_rule);
+                shapes.push(ring);
+            }
+        }
+    }
+
+    shapes
+}
+
+#[derive(Default)]
+struct PathRing {
+    points: Vec<Vector2f>,
+    segments: Vec<Segment>,
+}
+
+impl PathRing {
+    fn push(&mut self, point: Vector2f, segment: Segment) {
+        if !self.points.is_empty() {
+            self.segments.push(segment);
+        }
+        self.points.push(point);
+    }
+
+    fn finish(mut self, closed: bool) -> Option<Shape> {
+        let first = *self.points.first()?;
+        let last = *self.points.last()?;
+        if closed && self.points.len() > 1 && first.distance_to(last) < 1e-3 {
+            self.points.pop();
+        } else if closed {
+            self.segments.push(Segment::Line);
+        }
+
+        let mut shape = Shape::from_points(self.points);
+        shape.set_closed(closed);
+        for (i, segment) in self.segments.into_iter().enumerate() {
+            shape.set_segment(i, segment);
+        }
+
+        Some(shape)
+    }
+}
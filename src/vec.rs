@@ -1,24 +1,78 @@
-use std::ops::{Add, Div, Mul, Sub};
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign,
+};
 
-use num::Num;
+use num::{Float, Num};
 use serde::{ser::SerializeSeq, Deserialize, Serialize, Serializer};
 
 pub type Vector2f = Vector<f32, 2>;
+pub type Vector3f = Vector<f32, 3>;
+pub type Vector4f = Vector<f32, 4>;
 
-#[allow(dead_code)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Vector<T: Num + Copy, const N: usize> {
     data: [T; N],
 }
 
-#[allow(dead_code)]
-impl<T: Num + Copy> Vector<T, 2> {
-    pub fn new(x: T, y: T) -> Self {
-        Self { data: [x, y] }
+impl<T: Num + Copy, const N: usize> Vector<T, N> {
+    pub fn from_array(data: [T; N]) -> Self {
+        Self { data }
     }
 
     pub fn zero() -> Self {
-        Self { data: [T::zero(), T::zero()] }
+        Self { data: [T::zero(); N] }
+    }
+
+    pub fn as_array(&self) -> &[T; N] {
+        &self.data
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.data.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.data.iter_mut()
+    }
+
+    pub fn dot(&self, other: Vector<T, N>) -> T {
+        self.data
+            .iter()
+            .zip(other.data.iter())
+            .fold(T::zero(), |acc, (a, b)| acc + *a * *b)
+    }
+
+    fn map(self, f: impl Fn(T) -> T) -> Self {
+        Self { data: self.data.map(f) }
+    }
+
+    fn zip_map(self, other: Self, f: impl Fn(T, T) -> T) -> Self {
+        let mut data = self.data;
+        for (a, b) in data.iter_mut().zip(other.data) {
+            *a = f(*a, b);
+        }
+        Self { data }
+    }
+}
+
+impl<T: Float, const N: usize> Vector<T, N> {
+    pub fn length(&self) -> T {
+        self.dot(*self).sqrt()
+    }
+
+    pub fn normalize(&self) -> Vector<T, N> {
+        let length = self.length();
+        self.map(|v| v / length)
+    }
+
+    pub fn distance_to(&self, other: Vector<T, N>) -> T {
+        (*self - other).length()
+    }
+}
+
+impl<T: Num + Copy> Vector<T, 2> {
+    pub fn new(x: T, y: T) -> Self {
+        Self { data: [x, y] }
     }
 
     pub fn x(&self) -> T {
@@ -38,31 +92,59 @@ impl<T: Num + Copy> Vector<T, 2> {
     }
 }
 
-#[allow(dead_code)]
-impl Vector<f32, 2> {
-    pub fn distance_to(&self, other: Vector<f32, 2>) -> f32 {
-        let x = self.x() - other.x();
-        let y = self.y() - other.y();
-        (x * x + y * y).sqrt()
+impl<T: Num + Copy> Vector<T, 3> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self { data: [x, y, z] }
     }
 
-    pub fn angle_between(&self, other: Vector<f32, 2>) -> f32 {
-        let x = self.x() - other.x();
-        let y = self.y() - other.y();
-        y.atan2(x)
+    pub fn x(&self) -> T {
+        self.data[0]
     }
 
-    pub fn normalize(&self) -> Vector<f32, 2> {
-        let length = self.length();
-        Vector::new(self.x() / length, self.y() / length)
+    pub fn y(&self) -> T {
+        self.data[1]
+    }
+
+    pub fn z(&self) -> T {
+        self.data[2]
     }
 
-    pub fn length(&self) -> f32 {
-        (self.x() * self.x() + self.y() * self.y()).sqrt()
+    pub fn cross(&self, other: Vector<T, 3>) -> Vector<T, 3> {
+        Vector::<T, 3>::new(
+            self.y() * other.z() - self.z() * other.y(),
+            self.z() * other.x() - self.x() * other.z(),
+            self.x() * other.y() - self.y() * other.x(),
+        )
     }
+}
 
-    pub fn dot(&self, other: Vector<f32, 2>) -> f32 {
-        self.x() * other.x() + self.y() * other.y()
+impl<T: Num + Copy> Vector<T, 4> {
+    pub fn new(x: T, y: T, z: T, w: T) -> Self {
+        Self { data: [x, y, z, w] }
+    }
+
+    pub fn x(&self) -> T {
+        self.data[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.data[1]
+    }
+
+    pub fn z(&self) -> T {
+        self.data[2]
+    }
+
+    pub fn w(&self) -> T {
+        self.data[3]
+    }
+}
+
+impl Vector<f32, 2> {
+    pub fn angle_between(&self, other: Vector<f32, 2>) -> f32 {
+        let x = self.x() - other.x();
+        let y = self.y() - other.y();
+        y.atan2(x)
     }
 
     pub fn cross(&self, other: Vector<f32, 2>) -> f32 {
@@ -76,86 +158,158 @@ impl Vector<f32, 2> {
     pub fn rotate(&self, angle: f32) -> Vector<f32, 2> {
         let x = self.x() * angle.cos() - self.y() * angle.sin();
         let y = self.x() * angle.sin() + self.y() * angle.cos();
-        Vector::new(x, y)
+        Vector::<f32, 2>::new(x, y)
+    }
+}
+
+impl<T: Num + Copy, const N: usize> Default for Vector<T, N> {
+    fn default() -> Self {
+        Self::zero()
     }
 }
 
-impl<T: Num + Copy> Add<Self> for Vector<T, 2> {
+impl<T: Num + Copy, const N: usize> Add<Self> for Vector<T, N> {
     type Output = Self;
 
-    fn add(self, other: Vector<T, 2>) -> Self {
-        Self {
-            data: [self.data[0] + other.data[0], self.data[1] + other.data[1]],
-        }
+    fn add(self, other: Vector<T, N>) -> Self {
+        self.zip_map(other, |a, b| a + b)
     }
 }
 
-impl<T: Num + Copy> Sub<Self> for Vector<T, 2> {
+impl<T: Num + Copy, const N: usize> Sub<Self> for Vector<T, N> {
     type Output = Self;
 
-    fn sub(self, other: Vector<T, 2>) -> Self {
-        Self {
-            data: [self.data[0] - other.data[0], self.data[1] - other.data[1]],
-        }
+    fn sub(self, other: Vector<T, N>) -> Self {
+        self.zip_map(other, |a, b| a - b)
     }
 }
 
-impl<T: Num + Copy> Mul<T> for Vector<T, 2> {
+impl<T: Num + Copy, const N: usize> Mul<T> for Vector<T, N> {
     type Output = Self;
 
     fn mul(self, other: T) -> Self {
-        Self {
-            data: [self.data[0] * other, self.data[1] * other],
-        }
+        self.map(|a| a * other)
     }
 }
 
-impl<T: Num + Copy> Div<T> for Vector<T, 2> {
+impl<T: Num + Copy, const N: usize> Div<T> for Vector<T, N> {
     type Output = Self;
 
     fn div(self, other: T) -> Self {
-        Self {
-            data: [self.data[0] / other, self.data[1] / other],
-        }
+        self.map(|a| a / other)
     }
 }
 
-impl Serialize for Vector2f {
+impl<T: Num + Copy + Neg<Output = T>, const N: usize> Neg for Vector<T, N> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        self.map(|a| -a)
+    }
+}
+
+impl<T: Num + Copy, const N: usize> AddAssign<Self> for Vector<T, N> {
+    fn add_assign(&mut self, other: Vector<T, N>) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Num + Copy, const N: usize> SubAssign<Self> for Vector<T, N> {
+    fn sub_assign(&mut self, other: Vector<T, N>) {
+        *self = *self - other;
+    }
+}
+
+impl<T: Num + Copy, const N: usize> MulAssign<T> for Vector<T, N> {
+    fn mul_assign(&mut self, other: T) {
+        *self = *self * other;
+    }
+}
+
+impl<T: Num + Copy, const N: usize> DivAssign<T> for Vector<T, N> {
+    fn div_assign(&mut self, other: T) {
+        *self = *self / other;
+    }
+}
+
+impl<T: Num + Copy, const N: usize> Index<usize> for Vector<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        &self.data[index]
+    }
+}
+
+impl<T: Num + Copy, const N: usize> IndexMut<usize> for Vector<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        &mut self.data[index]
+    }
+}
+
+impl<T: Num + Copy, const N: usize> IntoIterator for Vector<T, N> {
+    type Item = T;
+    type IntoIter = std::array::IntoIter<T, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a, T: Num + Copy, const N: usize> IntoIterator for &'a Vector<T, N> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
+    }
+}
+
+impl<T: Num + Copy + Serialize, const N: usize> Serialize for Vector<T, N> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut seq = serializer.serialize_seq(Some(2))?;
-        seq.serialize_element(&self.x())?;
-        seq.serialize_element(&self.y())?;
+        let mut seq = serializer.serialize_seq(Some(N))?;
+        for value in self.data.iter() {
+            seq.serialize_element(value)?;
+        }
         seq.end()
     }
 }
 
-impl<'de> Deserialize<'de> for Vector2f {
+impl<'de, T: Num + Copy + Deserialize<'de>, const N: usize> Deserialize<'de> for Vector<T, N> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
-        D: serde::Deserializer<'de> {
-        struct Vector2fVisitor;
+        D: serde::Deserializer<'de>,
+    {
+        struct VectorVisitor<T, const N: usize>(std::marker::PhantomData<T>);
 
-        impl<'de> serde::de::Visitor<'de> for Vector2fVisitor {
-            type Value = Vector2f;
+        impl<'de, T: Num + Copy + Deserialize<'de>, const N: usize> serde::de::Visitor<'de>
+            for VectorVisitor<T, N>
+        {
+            type Value = Vector<T, N>;
 
             fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
-                formatter.write_str("a 2D vector")
+                write!(formatter, "a {}D vector", N)
             }
 
             fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
             where
                 A: serde::de::SeqAccess<'de>,
             {
-                let x = seq.next_element()?.ok_or_else(|| serde::de::Error::invalid_length(0, &self))?;
-                let y = seq.next_element()?.ok_or_else(|| serde::de::Error::invalid_length(1, &self))?;
-                Ok(Vector2f::new(x, y))
+                let mut vector = Vector::<T, N>::zero();
+                for i in 0..N {
+                    vector.data[i] = seq
+                        .next_element()?
+                        .ok_or_else(|| serde::de::Error::invalid_length(i, &self))?;
+                }
+                if seq.next_element::<serde::de::IgnoredAny>()?.is_some() {
+                    return Err(serde::de::Error::invalid_length(N + 1, &self));
+                }
+                Ok(vector)
             }
         }
 
-        deserializer.deserialize_seq(Vector2fVisitor)
+        deserializer.deserialize_seq(VectorVisitor::<T, N>(std::marker::PhantomData))
     }
 }
-